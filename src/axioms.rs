@@ -1,31 +1,188 @@
-// this source includes a cubic solver
-// ported from Robert Lang's Reference Finder
+// axiom 6 is ported from Robert Lang's Reference Finder
 // http://langorigami.com
 use math::Vector;
 use math::Line;
-use math::Rect;
-// todo, many of these tests assume that the boundary is a convex polygon
+use math::Boundary;
+use math::Scalar;
+use math::Tolerance;
+use math::polynomial::Settings;
+use math::polynomial;
+use std::fmt;
+// the boundary can be a Rect, a ConvexPolygon, a Polygon (which can be
+// non-convex with holes), or a reference to any of these. lines are clipped
+// with clip_all so that every piece of a line on the paper is considered,
+// and a fold is only valid if the crease exists where a point crosses it.
+// everything is generic over the number type, f64 unless asked otherwise.
+// axiom 6 solves a cubic. with an exact number type only the roots that
+// can be written exactly are used (see exact_roots), a cubic whose roots
+// can't be is an error rather than lines made from floats.
 
-const EPSILON: f64 = 1.0e-8;
+// the inputs to each of the seven axioms, in the same order as the
+// axiom functions take them
+#[derive(Debug, Copy, Clone)]
+pub enum Axiom<T = f64> {
+	// a fold through two points
+	Axiom1(Vector<T>, Vector<T>),
+	// a fold which brings the first point onto the second
+	Axiom2(Vector<T>, Vector<T>),
+	// a fold which brings the first line onto the second
+	Axiom3(Line<T>, Line<T>),
+	// a fold through the point, perpendicular to the line
+	Axiom4(Vector<T>, Line<T>),
+	// a fold through the first point, bringing the second point onto the line
+	Axiom5(Vector<T>, Vector<T>, Line<T>),
+	// a fold bringing the first point onto the first line
+	// and the second point onto the second line
+	Axiom6(Vector<T>, Vector<T>, Line<T>, Line<T>),
+	// a fold bringing the point onto the second line,
+	// perpendicular to the first line
+	Axiom7(Vector<T>, Line<T>, Line<T>)
+}
+
+impl<T> Axiom<T> {
+	pub fn number (&self) -> u8 {
+		match *self {
+			Axiom::Axiom1(..) => 1,
+			Axiom::Axiom2(..) => 2,
+			Axiom::Axiom3(..) => 3,
+			Axiom::Axiom4(..) => 4,
+			Axiom::Axiom5(..) => 5,
+			Axiom::Axiom6(..) => 6,
+			Axiom::Axiom7(..) => 7
+		}
+	}
+}
+
+// one fold line and how it was made. the branch is which of the axiom's
+// solutions this is (counted before invalid ones are removed, so the same
+// branch always means the same root or the same bisector). the mirrors are
+// the points which are brought together, (point, where it lands).
+#[derive(Debug, Clone)]
+pub struct Solution<T = f64> {
+	pub line: Line<T>,
+	pub axiom: Axiom<T>,
+	pub branch: usize,
+	pub mirrors: Vec<(Vector<T>, Vector<T>)>
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AxiomError {
+	// the solutions can't be written in the number type, like the roots of
+	// axiom 6's cubic (when none is a fraction) with exact numbers
+	NotRepresentable
+}
+
+impl fmt::Display for AxiomError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			AxiomError::NotRepresentable =>
+				write!(f, "the solutions can't be written exactly in this number type")
+		}
+	}
+}
+
+impl ::std::error::Error for AxiomError {}
+
+// (branch, line, mirrors) from each of the axioms
+type Branches<T> = Vec<(usize, Line<T>, Vec<(Vector<T>, Vector<T>)>)>;
+
+// every valid fold line for any axiom
+pub fn solve<T: Scalar, B: Boundary<T>> (axiom: &Axiom<T>, boundary: B) -> Result<Vec<Solution<T>>, AxiomError> {
+	solve_with(axiom, boundary, &Tolerance::default())
+}
+
+// solve, with points and lines this close counted as touching
+pub fn solve_with<T: Scalar, B: Boundary<T>> (
+	axiom: &Axiom<T>,
+	boundary: B,
+	tolerance: &Tolerance<T>
+) -> Result<Vec<Solution<T>>, AxiomError> {
+	let t = tolerance;
+	let branches = match axiom.clone() {
+		Axiom::Axiom1(a, b) => solve1(a, b, boundary, t),
+		Axiom::Axiom2(a, b) => solve2(a, b, boundary, t),
+		Axiom::Axiom3(a, b) => solve3(a, b, boundary, t),
+		Axiom::Axiom4(a, b) => solve4(a, b, boundary, t),
+		Axiom::Axiom5(p1, p2, l) => solve5(p1, p2, l, boundary, t),
+		Axiom::Axiom6(p1, p2, l1, l2) => solve6(p1, p2, l1, l2, boundary, t)?,
+		Axiom::Axiom7(p, l1, l2) => solve7(p, l1, l2, boundary, t)
+	};
+	return Ok(branches.into_iter()
+		.map(|(branch, line, mirrors)| Solution { line, axiom: axiom.clone(), branch, mirrors })
+		.collect());
+}
+
+impl<T: Scalar> Solution<T> {
+	pub fn residual (&self) -> f64 { verify(&self.axiom, self.line.clone()) }
+}
+
+fn two<T: Scalar> () -> T { T::one() + T::one() }
+
+// how far a point is from a line
+fn off<T: Scalar> (p: Vector<T>, l: Line<T>) -> T { (p.dot(l.u) - l.d).abs() }
+
+// how far a line is from satisfying the axiom, 0.0 is a perfect solution.
+// it's the largest of the distances between where a point lands and
+// where it should be, and for the perpendicular axioms, the cosine of the
+// angle between the two lines. this only checks the geometry, not the paper.
+// the measuring is done in the axiom's own numbers, the answer is a float.
+pub fn verify<T: Scalar> (axiom: &Axiom<T>, line: Line<T>) -> f64 {
+	let length = line.u.magnitude();
+	if Tolerance::default().zero(length.clone()) { return f64::INFINITY; }
+	let line = Line { u: line.u.scale(T::one() / length.clone()), d: line.d / length };
+	let reflect = |p: Vector<T>| line.reflect_vector(p);
+	let residuals = match axiom.clone() {
+		Axiom::Axiom1(a, b) => vec![off(a, line.clone()), off(b, line.clone())],
+		Axiom::Axiom2(a, b) => vec![reflect(a).distance_to(b)],
+		Axiom::Axiom3(a, b) => {
+			// two points on the first line, one unit apart, land on the second
+			let p = a.u.scale(a.d.clone());
+			let q = p.add(a.u.rotate90());
+			vec![off(reflect(p), b.clone()), off(reflect(q), b)]
+		},
+		Axiom::Axiom4(p, l) => vec![off(p, line.clone()), line.u.dot(l.u).abs()],
+		Axiom::Axiom5(p1, p2, l) => vec![off(p1, line.clone()), off(reflect(p2), l)],
+		Axiom::Axiom6(p1, p2, l1, l2) => vec![off(reflect(p1), l1), off(reflect(p2), l2)],
+		Axiom::Axiom7(p, l1, l2) => vec![line.u.dot(l1.u).abs(), off(reflect(p), l2)]
+	};
+	return residuals.iter().map(|r| r.to_f64()).fold(0.0, f64::max);
+}
+
+fn lines<T> (branches: Branches<T>) -> Vec<Line<T>> {
+	branches.into_iter().map(|(_, line, _)| line).collect()
+}
 
 // for testing axiom 1:
 // (maybe) make sure the paper connects continuously between the two points
 // (at least) make sure the points are contained in the paper, which will be
 //   satisfied by the first test
-pub fn axiom1 (a: Vector, b: Vector, boundary: Rect) -> Vec<Line> {
-	if !boundary.contains(a) || !boundary.contains(b) { return vec![] }
-	let u: Vector = b.subtract(a).rotate90().normalize();
-	let d: f64 = a.add(b).dot(u) / 2.0;
-	return vec![Line { u: u, d: d }];
+pub fn axiom1<T: Scalar, B: Boundary<T>> (a: Vector<T>, b: Vector<T>, boundary: B) -> Vec<Line<T>> {
+	lines(solve1(a, b, boundary, &Tolerance::default()))
+}
+
+fn solve1<T: Scalar, B: Boundary<T>> (a: Vector<T>, b: Vector<T>, boundary: B, tolerance: &Tolerance<T>) -> Branches<T> {
+	let inside = |p: Vector<T>| boundary.contains_with(p, tolerance);
+	if !inside(a.clone()) || !inside(b.clone()) { return vec![] }
+	let u: Vector<T> = b.subtract(a.clone()).rotate90().normalize();
+	let d: T = a.add(b).dot(u.clone()) / two();
+	return vec![(0, Line { u: u, d: d }, vec![])];
 }
 
 // for testing axiom 2:
-// make sure that the two points are inside the boundary
-pub fn axiom2 (a: Vector, b: Vector, boundary: Rect) -> Vec<Line> {
-	if !boundary.contains(a) || !boundary.contains(b) { return vec![] }
-	let u: Vector = b.subtract(a).normalize();
-	let d: f64 = a.add(b).dot(u) / 2.0;
-	return vec![Line { u: u, d: d }];
+// make sure that the two points are inside the boundary, and that the
+// paper between them (at the crease) is too
+pub fn axiom2<T: Scalar, B: Boundary<T>> (a: Vector<T>, b: Vector<T>, boundary: B) -> Vec<Line<T>> {
+	lines(solve2(a, b, boundary, &Tolerance::default()))
+}
+
+fn solve2<T: Scalar, B: Boundary<T>> (a: Vector<T>, b: Vector<T>, boundary: B, tolerance: &Tolerance<T>) -> Branches<T> {
+	let inside = |p: Vector<T>| boundary.contains_with(p, tolerance);
+	if !inside(a.clone()) || !inside(b.clone()) { return vec![] }
+	// the crease must exist where a crosses it on its way to b
+	if !inside(a.midpoint(b.clone())) { return vec![] }
+	let u: Vector<T> = b.subtract(a.clone()).normalize();
+	let d: T = a.add(b.clone()).dot(u.clone()) / two();
+	return vec![(0, Line { u: u, d: d }, vec![(a, b)])];
 }
 
 // for testing axiom 3:
@@ -33,45 +190,56 @@ pub fn axiom2 (a: Vector, b: Vector, boundary: Rect) -> Vec<Line> {
 // 2. for each solution (1 or 2), make solution a reflection line
 // 3. reflect one input paramter (should be on top of the other)
 //    and test for any point to be inside the other segment.
-pub fn axiom3 (a: Line, b: Line, boundary: Rect) -> Vec<Line> {
-	let seg_a = boundary.clip(a);
-	let seg_b = boundary.clip(b);
-	if !seg_a.0 || !seg_b.0 { println!("AX3 this should not happen"); return vec![]; }
+// a non-convex boundary can chop a line into many segments, test all of them
+pub fn axiom3<T: Scalar, B: Boundary<T>> (a: Line<T>, b: Line<T>, boundary: B) -> Vec<Line<T>> {
+	lines(solve3(a, b, boundary, &Tolerance::default()))
+}
+
+fn solve3<T: Scalar, B: Boundary<T>> (a: Line<T>, b: Line<T>, boundary: B, tolerance: &Tolerance<T>) -> Branches<T> {
+	let segs_a = boundary.clip_all_with(a.clone(), tolerance);
+	let segs_b = boundary.clip_all_with(b.clone(), tolerance);
+	// one of the input lines misses the paper entirely
+	if segs_a.is_empty() || segs_b.is_empty() { return vec![]; }
 	// get intersection and a test if they are parallel
-	let intersect = a.intersect(b);
-	// if lines are parallel only one solution exists
-	if !intersect.0 {
-		// special case, because this is a square, we don't need to test any further
-		return vec![ Line { u: a.u, d: (a.d + b.d * a.u.dot(b.u)) / 2.0 } ];
-	}
-	// 2 solutions
-	let u1 = a.u.add(b.u).normalize();
-	let u2 = a.u.subtract(b.u).normalize();
-	let d1 = intersect.1.dot(u1);
-	let d2 = intersect.1.dot(u2);
-	let solutions: Vec<Line> = vec![ Line { u: u1, d: d1 }, Line { u: u2, d: d2 } ];
+	let intersect = a.intersect_with(b.clone(), tolerance);
+	// if lines are parallel only one solution exists, otherwise 2 solutions
+	let solutions: Vec<Line<T>> = if !intersect.0 {
+		let d = (a.d.clone() + b.d * a.u.dot(b.u)) / two();
+		vec![ Line { u: a.u, d: d } ]
+	} else {
+		let u1 = a.u.add(b.u.clone()).normalize();
+		let u2 = a.u.subtract(b.u).normalize();
+		let d1 = intersect.1.dot(u1.clone());
+		let d2 = intersect.1.dot(u2.clone());
+		vec![ Line { u: u1, d: d1 }, Line { u: u2, d: d2 } ]
+	};
 	// are the solutions inside the page
 	let inside_test: Vec<bool> = solutions.iter()
-		.map(|line| boundary.clip(*line).0)
+		.map(|line| !boundary.clip_all_with(line.clone(), tolerance).is_empty())
 		// .map(|seg| true) // testing: ignore this check
 		.collect();
-	// seg_a will be the only one reflected
+	// segs_a will be the only ones reflected
 	let reflect_test: Vec<bool> = solutions.iter()
-		.map(|l| l.reflect_segment(seg_a.1))
-		.map(|seg| seg.quick_overlap(seg_b.1))
+		.map(|l| segs_a.iter().map(|seg| l.reflect_segment(seg.clone()))
+			.any(|seg| segs_b.iter().any(|other| seg.quick_overlap(other.clone()))))
 		// .map(|seg| true) // testing: ignore this check
 		.collect();
-	return solutions.iter().enumerate()
+	return solutions.into_iter().enumerate()
 		.filter(|(i, _line)| inside_test[*i] && reflect_test[*i])
-		.map(|(_i, line)| *line)
+		.map(|(i, line)| (i, line, vec![]))
 		.collect();
 }
 // for testing axiom 4:
 // check the intersection point
-pub fn axiom4 (a: Vector, b: Line, boundary: Rect) -> Vec<Line> {
+pub fn axiom4<T: Scalar, B: Boundary<T>> (a: Vector<T>, b: Line<T>, boundary: B) -> Vec<Line<T>> {
+	lines(solve4(a, b, boundary, &Tolerance::default()))
+}
+
+fn solve4<T: Scalar, B: Boundary<T>> (a: Vector<T>, b: Line<T>, boundary: B, tolerance: &Tolerance<T>) -> Branches<T> {
+	let inside = |p: Vector<T>| boundary.contains_with(p, tolerance);
 	let u = b.u.rotate90();
-	let d = a.dot(u);
-	let solution = Line {u, d};
+	let d = a.dot(u.clone());
+	let solution = Line { u: u.clone(), d };
 	// test the line before we return it
 	// shortest distance between the input point and the input line
 	let dist = b.d - a.dot(b.u);
@@ -80,180 +248,222 @@ pub fn axiom4 (a: Vector, b: Line, boundary: Rect) -> Vec<Line> {
 	let point = a.add(vector);
 	// 1. the point along the paralle line must be visible
 	// 2. prevent lines external and collinear to boundary point
-	let test1 = boundary.contains(point);
+	let test1 = inside(point);
 	// todo: I suspect there might be a simpler way to check this
 	//   without calling clip.
-	let (test2, _segment) = boundary.clip(solution);
-	return if test1 && test2 { vec![solution] } else { vec![] }
+	let (test2, _segment) = boundary.clip_with(solution.clone(), tolerance);
+	return if test1 && test2 { vec![(0, solution, vec![])] } else { vec![] }
 }
 
 // p1 is the point the line will pass through (does not move)
 // p2 is the point that will fold onto the line (moves)
-pub fn axiom5 (p1: Vector, p2: Vector, l: Line, boundary: Rect) -> Vec<Line> {
-	let p1base = p1.dot(l.u);
-	let a = l.d - p1base;
-	let c = p1.distance_to(p2);
-	if a > c { return vec![] }
-	let b = (c * c - a * a).sqrt();
+pub fn axiom5<T: Scalar, B: Boundary<T>> (p1: Vector<T>, p2: Vector<T>, l: Line<T>, boundary: B) -> Vec<Line<T>> {
+	lines(solve5(p1, p2, l, boundary, &Tolerance::default()))
+}
+
+fn solve5<T: Scalar, B: Boundary<T>> (p1: Vector<T>, p2: Vector<T>, l: Line<T>, boundary: B, tolerance: &Tolerance<T>) -> Branches<T> {
+	let inside = |p: Vector<T>| boundary.contains_with(p, tolerance);
+	let p1base = p1.dot(l.u.clone());
+	let a = l.d.clone() - p1base;
+	let c = p1.distance_to(p2.clone());
+	// a is signed, the line can be out of reach on either side of p1
+	if a.abs() > c { return vec![] }
+	let b = (c.clone() * c - a.clone() * a.clone()).sqrt();
 	let a_vec = l.u.scale(a);
 	let base_center = p1.add(a_vec);
-	let base_vector = l.u.rotate90().scale(b);
+	let base_vector = l.u.rotate90().scale(b.clone());
 	// if b is near 0 we have one solution, otherwise two
-	let mirrors: Vec<Vector> = if b < EPSILON { vec![base_center] }
+	let mirrors: Vec<Vector<T>> = if tolerance.zero(b.clone()) { vec![base_center] }
 		else { vec![
-			base_center.add(base_vector),
+			base_center.add(base_vector.clone()),
 			base_center.subtract(base_vector)
 		]};
 	// for each construction to be valid its mirror point must be in the boundary
-	return mirrors.iter()
-		.filter(|vec| boundary.contains(**vec))
-		.map(|vec| p2.subtract(*vec).normalize())
-		.map(|u| Line { u, d: p1.dot(u) })
-		.collect::<Vec<Line>>();
-}
-
-// cube root preserve sign
-fn cubrt (n: f64) -> f64 {
-	if n < 0.0 { -(-n).powf(1.0/3.0) } else { n.powf(1.0/3.0) }
+	// and the crease must exist where p2 crosses it on its way to the mirror
+	return mirrors.into_iter().enumerate()
+		.filter(|(_, vec)| inside(vec.clone()))
+		.filter(|(_, vec)| inside(vec.midpoint(p2.clone())))
+		.map(|(i, vec)| {
+			let u = p2.subtract(vec.clone()).normalize();
+			(i, Line { u: u.clone(), d: p1.dot(u) }, vec![(p2.clone(), vec)])
+		})
+		.collect();
 }
 
-// Robert Lang's cubic solver from Reference Finder
-fn polynomial (degree: u8, a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
-	// linear
-	if degree == 1 { return vec![-d / c]; }
-	else if degree == 2 {
-		// quadratic
-		let discriminant = c.powf(2.0) - (4.0 * b * d);
-		// no solution
-		if discriminant < -EPSILON { return vec![]; }
-		// one solution
-		let q1 = -c / (2.0 * b);
-		if discriminant < EPSILON {
-			return vec![q1];
-		}
-		// two solutions
-		let q2 = discriminant.sqrt() / (2.0 * b);
-		return vec![q1 + q2, q1 - q2];
-	} else if degree == 3 {
-		// cubic
-		// Cardano's formula. convert to depressed cubic
-		let a2 = b / a;
-		let a1 = c / a;
-		let a0 = d / a;
-		let q = (3.0 * a1 - a2.powf(2.0)) / 9.0;
-		let r = (9.0 * a2 * a1 - 27.0 * a0 - 2.0 * a2.powf(3.0)) / 54.0;
-		let d0 = q.powf(3.0) + r.powf(2.0);
-		let u = -a2 / 3.0;
-		// one solution
-		if d0 > 0.0 {
-			let sqrt_d0 = d0.sqrt();
-			let s = cubrt(r + sqrt_d0);
-			let t = cubrt(r - sqrt_d0);
-			return vec![u + s + t];
-		}
-		// two solutions
-		if d0.abs() < EPSILON {
-			let s = r.powf(1.0/3.0);
-			// let S = cubrt(R);
-			// instead of checking if S is NaN, check if R was negative
-			// if (isNaN(S)) { break; }
-			if r < 0.0 { return vec![]; }
-			return vec![u + 2.0 * s, u - s];
-		}
-		// three solutions
-		let sqrt_d0 = (-d0).sqrt();
-		let phi = sqrt_d0.atan2(r) / 3.0;
-		let r_s = (r.powf(2.0) - d0).powf(1.0/6.0);
-		let s_r = r_s * phi.cos();
-		let s_i = r_s * phi.sin();
-		return vec![
-			u + 2.0 * s_r,
-			u - s_r - 3.0_f64.sqrt() * s_i,
-			u - s_r + 3.0_f64.sqrt() * s_i
-		];
-	}
-	return vec![];
+pub fn axiom6<T: Scalar, B: Boundary<T>> (
+	p1: Vector<T>,
+	p2: Vector<T>,
+	l1: Line<T>,
+	l2: Line<T>,
+	boundary: B
+) -> Result<Vec<Line<T>>, AxiomError> {
+	solve6(p1, p2, l1, l2, boundary, &Tolerance::default()).map(lines)
 }
 
-pub fn axiom6 (
-	p1: Vector,
-	p2: Vector,
-	l1: Line,
-	l2: Line,
-	boundary: Rect
-) -> Vec<Line> {
+fn solve6<T: Scalar, B: Boundary<T>> (
+	p1: Vector<T>,
+	p2: Vector<T>,
+	l1: Line<T>,
+	l2: Line<T>,
+	boundary: B,
+	tolerance: &Tolerance<T>
+) -> Result<Branches<T>, AxiomError> {
+	let inside = |p: Vector<T>| boundary.contains_with(p, tolerance);
 	// at least pointA must not be on lineA
-	// for some reason this epsilon is much higher than 1e-6
-	if (1.0 - (l1.u.dot(p1) / l1.d)).abs() < 0.02 { return vec![]; }
+	if tolerance.zero(off(p1.clone(), l1.clone())) { return Ok(vec![]); }
 	// line vec is the first line's vector, along the line, not the normal
 	let line_vec = l1.u.rotate90();
-	let vec1 = p1.add(l1.u.scale(l1.d)).subtract(p2.scale(2.0));
-	let vec2 = l1.u.scale(l1.d).subtract(p1);
-	let c1 = p2.dot(l2.u) - l2.d;
-	let c2 = 2.0 * vec2.dot(line_vec);
-	let c3 = vec2.dot(vec2);
-	let c4 = vec1.add(vec2).dot(line_vec);
-	let c5 = vec1.dot(vec2);
-	let c6 = line_vec.dot(l2.u);
+	let foot = l1.u.scale(l1.d.clone());
+	let vec1 = p1.add(foot.clone()).subtract(p2.scale(two()));
+	let vec2 = foot.subtract(p1.clone());
+	let c1 = p2.dot(l2.u.clone()) - l2.d;
+	let c2 = two::<T>() * vec2.dot(line_vec.clone());
+	let c3 = vec2.dot(vec2.clone());
+	let c4 = vec1.add(vec2.clone()).dot(line_vec.clone());
+	let c5 = vec1.dot(vec2.clone());
+	let c6 = line_vec.dot(l2.u.clone());
 	let c7 = vec2.dot(l2.u);
-	let a = c6;
-	let b = c1 + c4 * c6 + c7;
-	let c = c1 * c2 + c5 * c6 + c4 * c7;
+	let a = c6.clone();
+	let b = c1.clone() + c4.clone() * c6.clone() + c7.clone();
+	let c = c1.clone() * c2 + c5.clone() * c6 + c4 * c7.clone();
 	let d = c1 * c3 + c5 * c7;
 	// construct the solution from the root, the solution being the parameter
 	// point reflected across the fold line, lying on the parameter line
-	let mut polynomial_degree: u8 = 0;
-	if c.abs() > EPSILON { polynomial_degree = 1; }
-	if b.abs() > EPSILON { polynomial_degree = 2; }
-	if a.abs() > EPSILON { polynomial_degree = 3; }
-	let roots = polynomial(polynomial_degree, a, b, c, d);
-	let mirrors1: Vec<Vector> = roots.iter()
-		.map(|n| l1.u.scale(l1.d).add(line_vec.scale(*n)))
+	// a cubic, or lower when the leading coefficients are zero
+	let settings = Settings { merge: tolerance.multiplicity, ..Settings::default() };
+	let coefficients = [a.to_f64(), b.to_f64(), c.to_f64(), d.to_f64()];
+	let floats: Vec<f64> = polynomial::solve_with(&coefficients, &settings)
+		.iter()
+		.map(|root| root.value)
+		.collect();
+	let roots: Vec<T> = if T::epsilon() > T::zero() {
+		floats.iter().map(|root| T::from_f64(*root)).collect()
+	} else {
+		exact_roots(vec![a, b, c, d], &floats)?
+	};
+	let mirrors1: Vec<Vector<T>> = roots.into_iter()
+		.map(|n| foot.add(line_vec.scale(n)))
 		.collect();
 	// this tuple temporarily stores (the point, the line's u vector)
-	let solutions: Vec<Line> = mirrors1.iter()
-		.map(|p| (p, p.subtract(p1).normalize()))
-		.map(|el| Line { u: el.1, d: el.1.dot(el.0.midpoint(p1)) })
+	let solutions: Vec<Line<T>> = mirrors1.iter()
+		.map(|p| (p, p.subtract(p1.clone()).normalize()))
+		.map(|el| Line { u: el.1.clone(), d: el.1.dot(el.0.midpoint(p1.clone())) })
 		.collect();
-	let mirrors2: Vec<Vector> = solutions.iter()
-		.map(|l| p2.add(l.u.scale(2.0 * (l.d - p2.dot(l.u)))))
+	let mirrors2: Vec<Vector<T>> = solutions.iter()
+		.map(|l| p2.add(l.u.scale(two::<T>() * (l.d.clone() - p2.dot(l.u.clone())))))
 		.collect();
-	let mut lines: Vec<Line> = vec![];
-	for i in 0..solutions.len() {
-		if boundary.contains(mirrors1[i])
-		&& boundary.contains(mirrors2[i]) {
-			lines.push(solutions[i]);
+	let mut lines: Branches<T> = vec![];
+	for (i, solution) in solutions.into_iter().enumerate() {
+		let (m1, m2) = (mirrors1[i].clone(), mirrors2[i].clone());
+		// both mirrors must be on the paper, and the crease must exist
+		// where each point crosses it, halfway between it and its mirror
+		if inside(m1.clone())
+		&& inside(m2.clone())
+		&& inside(m1.midpoint(p1.clone()))
+		&& inside(m2.midpoint(p2.clone())) {
+			lines.push((i, solution, vec![(p1.clone(), m1), (p2.clone(), m2)]));
 		}
 	}
 	// this style: need to implement FromIterator for Vec<Line>
 	// return solutions.iter().enumerate()
-	// 	.filter(|(i, el)| boundary.contains(mirrors1[*i])
-	// 		&& boundary.contains(mirrors2[*i]))
+	// 	.filter(|(i, el)| inside(mirrors1[*i])
+	// 		&& inside(mirrors2[*i]))
 	// 	.map(|(_, el)| el)
 	// 	.collect::<Vec<Line>>();
-	return lines;
+	return Ok(lines);
+}
+
+// the roots of axiom 6, for a number type with no tolerance, smallest first.
+// floats can't be used, a root that is a little off leaves its mirror a little
+// off the paper. a cubic's roots aren't always square roots, but if one root
+// is a fraction (found near a float root) the others are the roots of a
+// quadratic. a cubic without a fraction for a root can't be solved here.
+fn exact_roots<T: Scalar> (mut coefficients: Vec<T>, floats: &[f64]) -> Result<Vec<T>, AxiomError> {
+	while coefficients.first().is_some_and(|c| *c == T::zero()) { coefficients.remove(0); }
+	let two = || T::one() + T::one();
+	let mut roots: Vec<T> = match coefficients.len() {
+		2 => vec![-coefficients[1].clone() / coefficients[0].clone()],
+		3 => {
+			let (a, b, c) = (coefficients[0].clone(), coefficients[1].clone(), coefficients[2].clone());
+			let discriminant = b.clone() * b.clone() - two() * two() * a.clone() * c;
+			if discriminant < T::zero() { vec![] }
+			else if discriminant == T::zero() { vec![-b / (two() * a)] }
+			else {
+				let root = discriminant.sqrt();
+				vec![
+					(-b.clone() - root.clone()) / (two() * a.clone()),
+					(-b + root) / (two() * a)
+				]
+			}
+		},
+		4 => {
+			let fraction = floats.iter()
+				.map(|x| simple_fraction(*x))
+				.map(|(p, q)| T::from_f64(p) / T::from_f64(q))
+				.find(|x| coefficients.iter()
+					.fold(T::zero(), |sum, c| sum * x.clone() + c.clone()) == T::zero());
+			match fraction {
+				// divide out (x - r), leaving a quadratic
+				Some(r) => {
+					let a = coefficients[0].clone();
+					let b = coefficients[1].clone() + a.clone() * r.clone();
+					let c = coefficients[2].clone() + b.clone() * r.clone();
+					let mut roots = exact_roots(vec![a, b, c], &[])?;
+					roots.push(r);
+					roots
+				},
+				None => return Err(AxiomError::NotRepresentable)
+			}
+		},
+		_ => vec![]
+	};
+	roots.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+	roots.dedup_by(|a, b| *a == *b);
+	return Ok(roots);
+}
+
+// the continued fraction of a float, stopped when it is close enough
+fn simple_fraction (x: f64) -> (f64, f64) {
+	let (mut p0, mut q0, mut p1, mut q1) = (0.0, 1.0, 1.0, 0.0);
+	let mut rest = x;
+	for _ in 0..32 {
+		let whole = rest.floor();
+		let (p, q) = (whole * p1 + p0, whole * q1 + q0);
+		p0 = p1; q0 = q1; p1 = p; q1 = q;
+		if (x - p / q).abs() <= 1.0e-12 * x.abs().max(1.0) || q > 1.0e9 { break; }
+		rest = 1.0 / (rest - whole);
+		if !rest.is_finite() { break; }
+	}
+	return (p1, q1);
 }
 
 // l1 is the perpendicular to our solution
 // l2 is the line we bring the point onto
-pub fn axiom7 (p: Vector, l1: Line, l2: Line, boundary: Rect) -> Vec<Line> {
+pub fn axiom7<T: Scalar, B: Boundary<T>> (p: Vector<T>, l1: Line<T>, l2: Line<T>, boundary: B) -> Vec<Line<T>> {
+	lines(solve7(p, l1, l2, boundary, &Tolerance::default()))
+}
+
+fn solve7<T: Scalar, B: Boundary<T>> (p: Vector<T>, l1: Line<T>, l2: Line<T>, boundary: B, tolerance: &Tolerance<T>) -> Branches<T> {
+	let inside = |p: Vector<T>| boundary.contains_with(p, tolerance);
 	let u = l1.u.rotate90();
-	let u_u = u.dot(l2.u);
+	let u_u = u.dot(l2.u.clone());
 	// if u_u is close to 0, the two input lines are parallel, no solution
-	if u_u.abs() < EPSILON { return vec![] }
-	let a = p.dot(u);
+	if l1.u.parallel_with(l2.u.clone(), tolerance) { return vec![] }
+	let a = p.dot(u.clone());
 	let b = p.dot(l2.u);
-	let d = (l2.d + 2.0 * a * u_u - b) / (2.0 * u_u);
+	let d = (l2.d + two::<T>() * a * u_u.clone() - b) / (two::<T>() * u_u);
 	// test if construction is valid inside the boundary
 	let solution = Line {u, d};
-	let intersect = solution.intersect(l1);
-	let reflection = solution.reflect_vector(p);
+	let intersect = solution.intersect_with(l1, tolerance);
+	let reflection = solution.reflect_vector(p.clone());
 	// the reflected point should be inside the boundary
 	// todo: simplify this next line using variables above
-	let test1 = boundary.contains(reflection);
+	let test1 = inside(reflection.clone());
 	// if this intersection isn't inside, the line can't be folded onto itself
-	let test2 = intersect.0 && boundary.contains(intersect.1);
+	let test2 = intersect.0 && inside(intersect.1.clone());
 	// mirror should not be the intersection point itself
-	let test3 = !reflection.equivalent(intersect.1);
-	return if test1 && test2 && test3 { vec![solution] } else { vec![] };
+	let test3 = !reflection.equivalent_with(intersect.1, tolerance);
+	// the crease must exist where the point crosses it
+	let test4 = inside(reflection.midpoint(p.clone()));
+	return if test1 && test2 && test3 && test4 { vec![(0, solution, vec![(p, reflection)])] } else { vec![] };
 }
@@ -0,0 +1,353 @@
+// reading and writing the FOLD file format
+// https://github.com/edemaine/fold
+// every key of the file is kept, the keys that this library understands
+// are parsed into typed arrays, all others are carried along untouched
+// so that a file can be read and written back without losing anything.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use serde_json;
+use serde_json::Map;
+use serde_json::Value;
+use math::Vector;
+use graph::Graph;
+use graph::Assignment;
+
+#[derive(Debug)]
+pub enum FoldError {
+	Io(io::Error),
+	// the text is not valid JSON, with the line and column of the problem
+	Json(String, usize, usize),
+	// the top level (or a frame) is not a JSON object
+	NotAnObject,
+	// a key has a value of the wrong type
+	InvalidField(String, String),
+	// an array refers to an index that doesn't exist
+	IndexOutOfRange(String, usize, usize),
+	// two arrays which must have the same length don't
+	LengthMismatch(String, usize, usize),
+	// a key that is needed is absent
+	MissingField(String)
+}
+
+impl fmt::Display for FoldError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			FoldError::Io(ref e) => write!(f, "{}", e),
+			FoldError::Json(ref e, line, column) =>
+				write!(f, "invalid JSON at line {} column {}: {}", line, column, e),
+			FoldError::NotAnObject =>
+				write!(f, "a FOLD file (and each frame) must be a JSON object"),
+			FoldError::InvalidField(ref key, ref reason) =>
+				write!(f, "\"{}\": {}", key, reason),
+			FoldError::IndexOutOfRange(ref key, index, length) =>
+				write!(f, "\"{}\" refers to index {} but there are only {}", key, index, length),
+			FoldError::LengthMismatch(ref key, expected, found) =>
+				write!(f, "\"{}\" should have {} entries, found {}", key, expected, found),
+			FoldError::MissingField(ref key) =>
+				write!(f, "\"{}\" is missing", key)
+		}
+	}
+}
+
+impl ::std::error::Error for FoldError {}
+
+impl From<io::Error> for FoldError {
+	fn from (e: io::Error) -> FoldError { FoldError::Io(e) }
+}
+
+// one frame of a FOLD file. a key that is absent from the file is None.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frame {
+	pub vertices_coords: Option<Vec<Vector>>,
+	// the z of each vertex, if the file gives 3D coordinates (a folded
+	// state often does). it is written back out, the graph only uses x and y
+	pub vertices_z: Option<Vec<f64>>,
+	pub edges_vertices: Option<Vec<[usize; 2]>>,
+	pub edges_assignment: Option<Vec<Assignment>>,
+	// in degrees, positive is valley, negative is mountain
+	pub edges_fold_angle: Option<Vec<f64>>,
+	pub faces_vertices: Option<Vec<Vec<usize>>>,
+	// (f, g, order): 1 means f is above g (on the side g's normal points
+	// to), -1 means f is below g, 0 unknown
+	pub face_orders: Option<Vec<(usize, usize, i8)>>,
+	// every other key in the frame, like frame_title, frame_classes,
+	// vertices_vertices, or anything custom
+	pub other: Map<String, Value>
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FoldFile {
+	// the file_ keys: file_spec, file_creator, file_author, file_title...
+	pub metadata: Map<String, Value>,
+	// the top level of the file is the first frame, the rest are file_frames
+	pub frames: Vec<Frame>
+}
+
+const VERTICES_COORDS: &str = "vertices_coords";
+const EDGES_VERTICES: &str = "edges_vertices";
+const EDGES_ASSIGNMENT: &str = "edges_assignment";
+const EDGES_FOLD_ANGLE: &str = "edges_foldAngle";
+const FACES_VERTICES: &str = "faces_vertices";
+const FACE_ORDERS: &str = "faceOrders";
+const FILE_FRAMES: &str = "file_frames";
+
+impl Frame {
+	// build a graph from this frame. assignments default to unassigned,
+	// and if the frame has no faces they are computed from the edges.
+	// a frame without its own coordinates (one which inherits them) can't.
+	pub fn to_graph (&self) -> Result<Graph, FoldError> {
+		let mut graph = Graph::new();
+		graph.vertices_coords = self.vertices_coords.clone()
+			.ok_or_else(|| FoldError::MissingField(VERTICES_COORDS.to_string()))?;
+		graph.edges_vertices = self.edges_vertices.clone().unwrap_or_default();
+		graph.edges_assignment = self.edges_assignment.clone()
+			.unwrap_or_else(|| vec![Assignment::Unassigned; graph.edges_vertices.len()]);
+		match self.faces_vertices {
+			Some(ref faces) => graph.faces_vertices = faces.clone(),
+			None => graph.rebuild_faces()
+		}
+		return Ok(graph);
+	}
+	fn from_object (object: &Map<String, Value>) -> Result<Frame, FoldError> {
+		let mut frame = Frame::default();
+		for (key, value) in object.iter() {
+			match key.as_str() {
+				VERTICES_COORDS => {
+					let coords: Vec<(Vector, Option<f64>)> = array(key, value)?
+						.iter().map(|v| parse_coords(key, v)).collect::<Result<_, _>>()?;
+					if coords.iter().any(|c| c.1.is_some()) {
+						frame.vertices_z = Some(coords.iter().map(|c| c.1.unwrap_or(0.0)).collect());
+					}
+					frame.vertices_coords = Some(coords.into_iter().map(|c| c.0).collect());
+				},
+				EDGES_VERTICES => frame.edges_vertices = Some(array(key, value)?
+					.iter().map(|v| parse_indices(key, v)).map(|r| r.and_then(|e| match e.len() {
+						2 => Ok([e[0], e[1]]),
+						_ => Err(invalid(key, "each edge needs exactly 2 vertices"))
+					})).collect::<Result<_, _>>()?),
+				EDGES_ASSIGNMENT => frame.edges_assignment = Some(array(key, value)?
+					.iter().map(|v| v.as_str()
+						.and_then(|s| if s.len() == 1 { s.chars().next() } else { None })
+						.and_then(Assignment::from_char)
+						.ok_or_else(|| invalid(key, "expected one of M V B F U C")))
+					.collect::<Result<_, _>>()?),
+				EDGES_FOLD_ANGLE => frame.edges_fold_angle = Some(array(key, value)?
+					.iter().map(|v| v.as_f64().ok_or_else(|| invalid(key, "expected a number")))
+					.collect::<Result<_, _>>()?),
+				FACES_VERTICES => frame.faces_vertices = Some(array(key, value)?
+					.iter().map(|v| parse_indices(key, v)).collect::<Result<_, _>>()?),
+				FACE_ORDERS => frame.face_orders = Some(array(key, value)?
+					.iter().map(|v| parse_face_order(key, v)).collect::<Result<_, _>>()?),
+				_ => { frame.other.insert(key.clone(), value.clone()); }
+			}
+		}
+		return Ok(frame);
+	}
+	fn to_object (&self) -> Map<String, Value> {
+		let mut object = self.other.clone();
+		if let Some(ref coords) = self.vertices_coords {
+			object.insert(VERTICES_COORDS.to_string(), coords.iter().enumerate()
+				.map(|(i, v)| match self.vertices_z.as_ref().and_then(|z| z.get(i)) {
+					Some(z) => Value::Array(vec![number(v.x), number(v.y), number(*z)]),
+					None => Value::Array(vec![number(v.x), number(v.y)])
+				}).collect());
+		}
+		if let Some(ref edges) = self.edges_vertices {
+			object.insert(EDGES_VERTICES.to_string(), edges.iter()
+				.map(|e| Value::Array(vec![Value::from(e[0]), Value::from(e[1])])).collect());
+		}
+		if let Some(ref assignments) = self.edges_assignment {
+			object.insert(EDGES_ASSIGNMENT.to_string(), assignments.iter()
+				.map(|a| Value::from(a.to_char().to_string())).collect());
+		}
+		if let Some(ref angles) = self.edges_fold_angle {
+			object.insert(EDGES_FOLD_ANGLE.to_string(), angles.iter()
+				.map(|a| number(*a)).collect());
+		}
+		if let Some(ref faces) = self.faces_vertices {
+			object.insert(FACES_VERTICES.to_string(), faces.iter()
+				.map(|f| f.iter().map(|v| Value::from(*v)).collect::<Value>()).collect());
+		}
+		if let Some(ref orders) = self.face_orders {
+			object.insert(FACE_ORDERS.to_string(), orders.iter()
+				.map(|o| Value::Array(vec![Value::from(o.0), Value::from(o.1), Value::from(o.2)]))
+				.collect());
+		}
+		return object;
+	}
+	// indices must point to things that exist in this frame. the number of
+	// vertices is given, a frame which inherits from its parent may not have
+	// its own coordinates. other arrays that are missing skip their test.
+	fn validate (&self, vertices: usize) -> Result<(), FoldError> {
+		let edges = self.edges_vertices.as_ref().map(|e| e.len());
+		let faces = self.faces_vertices.as_ref().map(|f| f.len());
+		if let Some(ref list) = self.edges_vertices {
+			check_indices(EDGES_VERTICES, list.iter().flat_map(|e| e.iter()), vertices)?;
+		}
+		if let Some(ref list) = self.faces_vertices {
+			check_indices(FACES_VERTICES, list.iter().flat_map(|f| f.iter()), vertices)?;
+		}
+		if let (Some(n), Some(ref list)) = (faces, &self.face_orders) {
+			let pairs: Vec<usize> = list.iter().flat_map(|o| vec![o.0, o.1]).collect();
+			check_indices(FACE_ORDERS, pairs.iter(), n)?;
+		}
+		if let Some(ref list) = self.vertices_z {
+			if list.len() != vertices {
+				return Err(FoldError::LengthMismatch(VERTICES_COORDS.to_string(), vertices, list.len()));
+			}
+		}
+		if let (Some(n), Some(ref list)) = (edges, &self.edges_assignment) {
+			if list.len() != n {
+				return Err(FoldError::LengthMismatch(EDGES_ASSIGNMENT.to_string(), n, list.len()));
+			}
+		}
+		if let (Some(n), Some(ref list)) = (edges, &self.edges_fold_angle) {
+			if list.len() != n {
+				return Err(FoldError::LengthMismatch(EDGES_FOLD_ANGLE.to_string(), n, list.len()));
+			}
+		}
+		return Ok(());
+	}
+}
+
+impl From<&Graph> for Frame {
+	fn from (graph: &Graph) -> Frame {
+		Frame {
+			vertices_coords: Some(graph.vertices_coords.clone()),
+			edges_vertices: Some(graph.edges_vertices.clone()),
+			edges_assignment: Some(graph.edges_assignment.clone()),
+			faces_vertices: Some(graph.faces_vertices.clone()),
+			..Frame::default()
+		}
+	}
+}
+
+impl FoldFile {
+	// a single frame file containing this graph
+	pub fn from_graph (graph: &Graph) -> FoldFile {
+		let mut metadata = Map::new();
+		metadata.insert("file_spec".to_string(), Value::from(1.1));
+		metadata.insert("file_creator".to_string(), Value::from("rabbit-ear"));
+		FoldFile { metadata, frames: vec![Frame::from(graph)] }
+	}
+	pub fn parse (text: &str) -> Result<FoldFile, FoldError> {
+		let value: Value = serde_json::from_str(text)
+			.map_err(|e| FoldError::Json(e.to_string(), e.line(), e.column()))?;
+		let object = value.as_object().ok_or(FoldError::NotAnObject)?;
+		let mut metadata = Map::new();
+		let mut top = Map::new();
+		let mut frames = vec![];
+		for (key, value) in object.iter() {
+			if key == FILE_FRAMES {
+				for frame in array(key, value)?.iter() {
+					let frame = frame.as_object().ok_or(FoldError::NotAnObject)?;
+					frames.push(Frame::from_object(frame)?);
+				}
+			} else if key.starts_with("file_") {
+				metadata.insert(key.clone(), value.clone());
+			} else {
+				top.insert(key.clone(), value.clone());
+			}
+		}
+		frames.insert(0, Frame::from_object(&top)?);
+		for (i, frame) in frames.iter().enumerate() {
+			frame.validate(vertex_count(&frames, i))?;
+		}
+		return Ok(FoldFile { metadata, frames });
+	}
+	pub fn to_json (&self) -> Value {
+		let mut object = self.metadata.clone();
+		let mut frames = self.frames.iter().map(|f| f.to_object());
+		if let Some(top) = frames.next() { object.extend(top); }
+		let rest: Vec<Value> = frames.map(Value::Object).collect();
+		if !rest.is_empty() { object.insert(FILE_FRAMES.to_string(), Value::Array(rest)); }
+		return Value::Object(object);
+	}
+	pub fn to_string_pretty (&self) -> String {
+		serde_json::to_string_pretty(&self.to_json()).unwrap()
+	}
+	pub fn read<P: AsRef<Path>> (path: P) -> Result<FoldFile, FoldError> {
+		FoldFile::parse(&fs::read_to_string(path)?)
+	}
+	pub fn write<P: AsRef<Path>> (&self, path: P) -> Result<(), FoldError> {
+		fs::write(path, self.to_string_pretty())?;
+		return Ok(());
+	}
+}
+
+impl fmt::Display for FoldFile {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.to_json())
+	}
+}
+
+// the number of vertices of a frame, its own, or if it has none and
+// inherits from its parent, the parent's. a frame with neither has none.
+fn vertex_count (frames: &[Frame], index: usize) -> usize {
+	let mut i = index;
+	// a parent can't be more steps away than there are frames
+	for _ in 0..frames.len() {
+		let frame = &frames[i];
+		if let Some(ref coords) = frame.vertices_coords { return coords.len(); }
+		let inherits = frame.other.get("frame_inherit").and_then(|v| v.as_bool()) == Some(true);
+		match frame.other.get("frame_parent").and_then(|v| v.as_u64()) {
+			Some(parent) if inherits && (parent as usize) < frames.len() => i = parent as usize,
+			_ => return 0
+		}
+	}
+	return 0;
+}
+
+fn invalid (key: &str, reason: &str) -> FoldError {
+	FoldError::InvalidField(key.to_string(), reason.to_string())
+}
+
+fn array<'a> (key: &str, value: &'a Value) -> Result<&'a Vec<Value>, FoldError> {
+	value.as_array().ok_or_else(|| invalid(key, "expected an array"))
+}
+
+// serde_json can't store NaN or infinity, these become null
+fn number (n: f64) -> Value { Value::from(n) }
+
+// 2D coordinates, or 3D coordinates with their z
+fn parse_coords (key: &str, value: &Value) -> Result<(Vector, Option<f64>), FoldError> {
+	let numbers: Vec<f64> = array(key, value)?.iter()
+		.map(|n| n.as_f64().ok_or_else(|| invalid(key, "expected a number")))
+		.collect::<Result<_, _>>()?;
+	match numbers.len() {
+		2 => Ok((Vector { x: numbers[0], y: numbers[1] }, None)),
+		3 => Ok((Vector { x: numbers[0], y: numbers[1] }, Some(numbers[2]))),
+		_ => Err(invalid(key, "coordinates need 2 or 3 numbers"))
+	}
+}
+
+fn parse_index (key: &str, value: &Value) -> Result<usize, FoldError> {
+	value.as_u64().map(|n| n as usize)
+		.ok_or_else(|| invalid(key, "expected a non-negative integer"))
+}
+
+fn parse_indices (key: &str, value: &Value) -> Result<Vec<usize>, FoldError> {
+	array(key, value)?.iter().map(|v| parse_index(key, v)).collect()
+}
+
+fn parse_face_order (key: &str, value: &Value) -> Result<(usize, usize, i8), FoldError> {
+	let list = array(key, value)?;
+	if list.len() != 3 { return Err(invalid(key, "each face order needs 3 numbers")); }
+	let order = match list[2].as_i64() {
+		Some(n) if n >= -1 && n <= 1 => n as i8,
+		_ => return Err(invalid(key, "the order must be -1, 0, or 1"))
+	};
+	return Ok((parse_index(key, &list[0])?, parse_index(key, &list[1])?, order));
+}
+
+fn check_indices<'a, I> (key: &str, indices: I, length: usize) -> Result<(), FoldError>
+	where I: Iterator<Item = &'a usize> {
+	for i in indices {
+		if *i >= length {
+			return Err(FoldError::IndexOutOfRange(key.to_string(), *i, length));
+		}
+	}
+	return Ok(());
+}
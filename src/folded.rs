@@ -0,0 +1,62 @@
+// where the crease pattern goes when it is folded flat. one face stays
+// where it is, and the paper is walked face to face across the edges
+// (a breadth-first spanning tree). crossing a mountain, valley or
+// unassigned edge reflects the paper across the edge's line, crossing a flat
+// edge doesn't. the paper's edge and cuts can't be crossed.
+use std::collections::VecDeque;
+use math::Vector;
+use math::Line;
+use math::Matrix;
+use graph::Graph;
+use graph::Assignment;
+
+// the transform which moves each face from the crease pattern to its folded
+// position. None for faces which can't be reached from the root face.
+pub fn faces_matrix (graph: &Graph, root: usize) -> Vec<Option<Matrix>> {
+	let mut matrices: Vec<Option<Matrix>> = vec![None; graph.faces_vertices.len()];
+	if root >= matrices.len() { return matrices; }
+	let edges_faces = graph.edges_faces();
+	let faces_edges = graph.faces_edges();
+	matrices[root] = Some(Matrix::IDENTITY);
+	let mut queue = VecDeque::new();
+	queue.push_back(root);
+	while let Some(face) = queue.pop_front() {
+		let matrix = matrices[face].unwrap();
+		for edge in faces_edges[face].iter() {
+			let assignment = graph.edges_assignment[*edge];
+			if assignment.is_boundary() { continue; }
+			for next in edges_faces[*edge].iter() {
+				if matrices[*next].is_some() { continue; }
+				matrices[*next] = Some(match assignment {
+					Assignment::Flat => matrix,
+					_ => {
+						let segment = graph.edge_segment(*edge);
+						let u = segment.b.subtract(segment.a).rotate90().normalize();
+						matrix.multiply(Matrix::reflection(Line { u, d: segment.a.dot(u) }))
+					}
+				});
+				queue.push_back(*next);
+			}
+		}
+	}
+	return matrices;
+}
+
+// the folded position of every vertex, moved by the first face it belongs to.
+// vertices which aren't part of any reachable face stay where they are.
+pub fn vertices_coords_folded (graph: &Graph, root: usize) -> Vec<Vector> {
+	let matrices = faces_matrix(graph, root);
+	let mut folded: Vec<Option<Vector>> = vec![None; graph.vertices_coords.len()];
+	for (face, vertices) in graph.faces_vertices.iter().enumerate() {
+		let matrix = match matrices[face] {
+			Some(matrix) => matrix,
+			None => continue
+		};
+		for v in vertices.iter() {
+			if folded[*v].is_none() { folded[*v] = Some(matrix.transform(graph.vertices_coords[*v])); }
+		}
+	}
+	return folded.iter().enumerate()
+		.map(|(v, p)| p.unwrap_or(graph.vertices_coords[v]))
+		.collect();
+}
@@ -0,0 +1,403 @@
+use std::fmt;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use math::Vector;
+use math::Line;
+use math::Segment;
+use math::Rect;
+use math::ConvexPolygon;
+use math::Polygon;
+use math::EPSILON;
+use math::Tolerance;
+use math::signed_area;
+use math::winding;
+use planarize::planarize;
+
+// the assignment of an edge, using the same letters as the FOLD format
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Assignment {
+	Mountain,
+	Valley,
+	Boundary,
+	Flat,
+	Unassigned,
+	Cut
+}
+
+impl Assignment {
+	// accepts upper or lower case
+	pub fn from_char (c: char) -> Option<Assignment> {
+		match c.to_ascii_uppercase() {
+			'M' => Some(Assignment::Mountain),
+			'V' => Some(Assignment::Valley),
+			'B' => Some(Assignment::Boundary),
+			'F' => Some(Assignment::Flat),
+			'U' => Some(Assignment::Unassigned),
+			'C' => Some(Assignment::Cut),
+			_ => None
+		}
+	}
+	pub fn to_char (self) -> char {
+		match self {
+			Assignment::Mountain => 'M',
+			Assignment::Valley => 'V',
+			Assignment::Boundary => 'B',
+			Assignment::Flat => 'F',
+			Assignment::Unassigned => 'U',
+			Assignment::Cut => 'C'
+		}
+	}
+	// mountain and valley swap, everything else is unchanged
+	pub fn flip (self) -> Assignment {
+		match self {
+			Assignment::Mountain => Assignment::Valley,
+			Assignment::Valley => Assignment::Mountain,
+			other => other
+		}
+	}
+	// boundary and cut edges are not creases, they are the edge of the paper
+	pub fn is_boundary (self) -> bool {
+		self == Assignment::Boundary || self == Assignment::Cut
+	}
+}
+
+impl fmt::Display for Assignment {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.to_char())
+	}
+}
+
+// a planar graph of a crease pattern. the field names follow the FOLD
+// format, edges and faces refer to vertices by their index.
+// the faces are counter-clockwise and are rebuilt every time an edge is added.
+#[derive(Clone, Default)]
+pub struct Graph {
+	pub vertices_coords: Vec<Vector>,
+	pub edges_vertices: Vec<[usize; 2]>,
+	pub edges_assignment: Vec<Assignment>,
+	pub faces_vertices: Vec<Vec<usize>>
+}
+
+impl Graph {
+	pub fn new () -> Graph { Graph::default() }
+	// a sheet of paper: a single face surrounded by boundary edges
+	pub fn from_points (points: &[Vector]) -> Graph {
+		let mut graph = Graph::new();
+		graph.add_ring(points);
+		graph.rebuild_faces();
+		return graph;
+	}
+	// a sheet with holes. faces are cycles of edges, the face surrounding
+	// a hole will not know about it (the same as in the FOLD format).
+	pub fn from_polygon (polygon: &Polygon) -> Graph {
+		let mut graph = Graph::new();
+		graph.add_ring(polygon.outer());
+		for hole in polygon.holes() { graph.add_ring(hole); }
+		graph.rebuild_faces();
+		return graph;
+	}
+	// a crease pattern from loose segments, where crossing segments
+	// split each other, like the lines of a drawing. see planarize.
+	pub fn from_segments (segments: &[(Segment, Assignment)]) -> Graph {
+		planarize(segments, &Tolerance::default())
+	}
+	// a clean copy of this graph with every crossing and overlap resolved
+	// and the vertices closer than the tolerance welded together
+	pub fn planarize (&self, tolerance: &Tolerance) -> Graph {
+		planarize(&self.assigned_segments(), tolerance)
+	}
+	fn add_ring (&mut self, points: &[Vector]) {
+		let start = self.vertices_coords.len();
+		let n = points.len();
+		self.vertices_coords.extend_from_slice(points);
+		for i in 0..n {
+			self.edges_vertices.push([start + i, start + (i + 1) % n]);
+			self.edges_assignment.push(Assignment::Boundary);
+		}
+	}
+
+	// queries
+	pub fn edge_segment (&self, edge: usize) -> Segment {
+		let [a, b] = self.edges_vertices[edge];
+		Segment { a: self.vertices_coords[a], b: self.vertices_coords[b] }
+	}
+	pub fn segments (&self) -> Vec<Segment> {
+		(0..self.edges_vertices.len()).map(|e| self.edge_segment(e)).collect()
+	}
+	// every edge as a segment paired with its assignment
+	pub fn assigned_segments (&self) -> Vec<(Segment, Assignment)> {
+		self.segments().into_iter().zip(self.edges_assignment.iter().cloned()).collect()
+	}
+	// the index of the edge connecting the two vertices, in either direction
+	pub fn edge_between (&self, a: usize, b: usize) -> Option<usize> {
+		self.edges_vertices.iter()
+			.position(|e| (e[0] == a && e[1] == b) || (e[0] == b && e[1] == a))
+	}
+	pub fn nearest_vertex (&self, p: Vector) -> Option<usize> {
+		self.vertices_coords.iter().enumerate()
+			.map(|(i, v)| (i, v.distance_to(p)))
+			.fold(None, |nearest: Option<(usize, f64)>, (i, d)| match nearest {
+				Some((_, n)) if n <= d => nearest,
+				_ => Some((i, d))
+			})
+			.map(|(i, _)| i)
+	}
+	pub fn vertices_edges (&self) -> Vec<Vec<usize>> {
+		let mut vertices_edges = vec![vec![]; self.vertices_coords.len()];
+		for (e, [a, b]) in self.edges_vertices.iter().enumerate() {
+			vertices_edges[*a].push(e);
+			vertices_edges[*b].push(e);
+		}
+		return vertices_edges;
+	}
+	// the neighbors of each vertex, sorted counter-clockwise by angle
+	pub fn vertices_vertices (&self) -> Vec<Vec<usize>> {
+		let mut vertices_vertices = vec![vec![]; self.vertices_coords.len()];
+		for [a, b] in self.edges_vertices.iter() {
+			vertices_vertices[*a].push(*b);
+			vertices_vertices[*b].push(*a);
+		}
+		for (v, adjacent) in vertices_vertices.iter_mut().enumerate() {
+			let origin = self.vertices_coords[v];
+			let angle = |i: &usize| {
+				let vec = self.vertices_coords[*i].subtract(origin);
+				vec.y.atan2(vec.x)
+			};
+			adjacent.sort_by(|a, b| angle(a).partial_cmp(&angle(b)).unwrap());
+		}
+		return vertices_vertices;
+	}
+	pub fn faces_edges (&self) -> Vec<Vec<usize>> {
+		let lookup = self.edge_lookup();
+		self.faces_vertices.iter()
+			.map(|face| (0..face.len())
+				.map(|i| lookup[&sorted_pair(face[i], face[(i + 1) % face.len()])])
+				.collect())
+			.collect()
+	}
+	pub fn edges_faces (&self) -> Vec<Vec<usize>> {
+		let mut edges_faces = vec![vec![]; self.edges_vertices.len()];
+		for (f, edges) in self.faces_edges().iter().enumerate() {
+			for e in edges {
+				if !edges_faces[*e].contains(&f) { edges_faces[*e].push(f); }
+			}
+		}
+		return edges_faces;
+	}
+	pub fn vertices_faces (&self) -> Vec<Vec<usize>> {
+		let mut vertices_faces = vec![vec![]; self.vertices_coords.len()];
+		for (f, face) in self.faces_vertices.iter().enumerate() {
+			for v in face {
+				if !vertices_faces[*v].contains(&f) { vertices_faces[*v].push(f); }
+			}
+		}
+		return vertices_faces;
+	}
+	fn edge_lookup (&self) -> HashMap<(usize, usize), usize> {
+		self.edges_vertices.iter().enumerate()
+			.map(|(e, [a, b])| (sorted_pair(*a, *b), e))
+			.collect()
+	}
+	pub fn face_polygon (&self, face: usize) -> Vec<Vector> {
+		self.faces_vertices[face].iter().map(|v| self.vertices_coords[*v]).collect()
+	}
+	pub fn face_contains (&self, face: usize, p: Vector) -> bool {
+		let polygon = self.face_polygon(face);
+		let n = polygon.len();
+		// points on the edge of the face count as inside
+		let on_edge = (0..n).any(|i| {
+			let segment = Segment { a: polygon[i], b: polygon[(i + 1) % n] };
+			segment.contains(p)
+		});
+		return on_edge || winding(&polygon, p) != 0;
+	}
+
+	// modifiers
+	// get the vertex at this point, welding it to a nearby vertex if one
+	// exists, or splitting an edge if the point lies along it.
+	pub fn add_vertex (&mut self, p: Vector) -> usize {
+		if let Some(v) = self.vertices_coords.iter().position(|v| v.equivalent(p)) {
+			return v;
+		}
+		let edge = (0..self.edges_vertices.len())
+			.find(|e| self.edge_segment(*e).contains(p));
+		match edge {
+			Some(e) => self.split_edge(e, p),
+			None => {
+				self.vertices_coords.push(p);
+				self.vertices_coords.len() - 1
+			}
+		}
+	}
+	// the edge keeps its index and is shortened, a new edge is added with
+	// the same assignment. faces that include the edge gain the new vertex.
+	pub fn split_edge (&mut self, edge: usize, p: Vector) -> usize {
+		let v = self.vertices_coords.len();
+		self.vertices_coords.push(p);
+		let [a, b] = self.edges_vertices[edge];
+		self.edges_vertices[edge] = [a, v];
+		self.edges_vertices.push([v, b]);
+		let assignment = self.edges_assignment[edge];
+		self.edges_assignment.push(assignment);
+		for face in self.faces_vertices.iter_mut() {
+			let n = face.len();
+			if let Some(i) = (0..n).find(|i| {
+				let (c, d) = (face[*i], face[(*i + 1) % n]);
+				(c == a && d == b) || (c == b && d == a)
+			}) {
+				face.insert(i + 1, v);
+			}
+		}
+		return v;
+	}
+	// add a segment, splitting it where it crosses existing edges and
+	// splitting those edges too. the faces are rebuilt.
+	// @returns the indices of the new edges
+	pub fn add_segment (&mut self, segment: Segment, assignment: Assignment) -> Vec<usize> {
+		let vector = segment.b.subtract(segment.a);
+		let length_sq = vector.magnitude_squared();
+		if length_sq < EPSILON { return vec![]; }
+		// every point along the segment where a new vertex is needed
+		let mut points: Vec<(f64, Vector)> = vec![(0.0, segment.a), (1.0, segment.b)];
+		for e in 0..self.edges_vertices.len() {
+			let edge = self.edge_segment(e);
+			match segment.intersect_segment(edge) {
+				Some((p, t, _)) => points.push((t, p)),
+				// parallel edges that overlap touch the segment at their ends
+				None => for p in [edge.a, edge.b] {
+					if segment.contains(p) { points.push((segment.parameter(p), p)); }
+				},
+			}
+		}
+		points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		let mut vertices: Vec<usize> = points.iter()
+			.map(|(_, p)| self.add_vertex(*p))
+			.collect();
+		vertices.dedup();
+		let mut new_edges = vec![];
+		for pair in vertices.windows(2) {
+			if self.edge_between(pair[0], pair[1]).is_some() { continue; }
+			self.edges_vertices.push([pair[0], pair[1]]);
+			self.edges_assignment.push(assignment);
+			new_edges.push(self.edges_vertices.len() - 1);
+		}
+		self.rebuild_faces();
+		return new_edges;
+	}
+	// add a fold line (like one from the axioms) across the entire sheet.
+	// only the parts of the line which lie inside a face are added.
+	// @returns the indices of the new edges
+	pub fn add_line (&mut self, line: Line, assignment: Assignment) -> Vec<usize> {
+		let origin = line.u.scale(line.d);
+		let vector = line.u.rotate90();
+		let mut ts: Vec<f64> = vec![];
+		for e in 0..self.edges_vertices.len() {
+			let edge = self.edge_segment(e);
+			let side_a = edge.a.dot(line.u) - line.d;
+			let side_b = edge.b.dot(line.u) - line.d;
+			if side_a.abs() < EPSILON { ts.push(edge.a.subtract(origin).dot(vector)); }
+			if side_b.abs() < EPSILON { ts.push(edge.b.subtract(origin).dot(vector)); }
+			if (side_a < -EPSILON && side_b > EPSILON)
+			|| (side_a > EPSILON && side_b < -EPSILON) {
+				let p = edge.a.add(edge.b.subtract(edge.a).scale(side_a / (side_a - side_b)));
+				ts.push(p.subtract(origin).dot(vector));
+			}
+		}
+		ts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		ts.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+		let faces = 0..self.faces_vertices.len();
+		let segments: Vec<Segment> = ts.windows(2)
+			.filter(|pair| {
+				let middle = origin.add(vector.scale((pair[0] + pair[1]) / 2.0));
+				faces.clone().any(|f| self.face_contains(f, middle))
+			})
+			.map(|pair| Segment {
+				a: origin.add(vector.scale(pair[0])),
+				b: origin.add(vector.scale(pair[1]))
+			})
+			.collect();
+		return segments.iter()
+			.flat_map(|s| self.add_segment(*s, assignment))
+			.collect();
+	}
+	// is the point on the paper: a ray from it to the right crosses the
+	// boundary edges an odd number of times. this works for paper with
+	// holes. a graph without boundary edges has no outside.
+	fn on_paper (&self, p: Vector) -> bool {
+		let boundary: Vec<Segment> = (0..self.edges_vertices.len())
+			.filter(|e| self.edges_assignment[*e] == Assignment::Boundary)
+			.map(|e| self.edge_segment(e))
+			.collect();
+		if boundary.is_empty() { return true; }
+		let crossings = boundary.iter()
+			.filter(|s| (s.a.y > p.y) != (s.b.y > p.y))
+			.filter(|s| s.a.x + (p.y - s.a.y) / (s.b.y - s.a.y) * (s.b.x - s.a.x) > p.x)
+			.count();
+		return crossings % 2 == 1;
+	}
+	// walk every edge on both sides, turning at each vertex to the next
+	// edge clockwise. counter-clockwise walks are the faces, the one
+	// clockwise walk (per connected piece) is the outside and is ignored.
+	// the inside of a hole is also a counter-clockwise walk, it's ignored
+	// because it isn't on the paper. a face surrounding a hole can have the
+	// hole in the middle of it, so the test is a point just inside its
+	// longest edge (the face is on the left of each edge).
+	pub fn rebuild_faces (&mut self) {
+		let vertices_vertices = self.vertices_vertices();
+		let mut visited: HashSet<(usize, usize)> = HashSet::new();
+		let mut faces: Vec<Vec<usize>> = vec![];
+		for [a, b] in self.edges_vertices.iter() {
+			for &(start, next) in [(*a, *b), (*b, *a)].iter() {
+				if visited.contains(&(start, next)) { continue; }
+				let mut face = vec![];
+				let (mut from, mut to) = (start, next);
+				while !visited.contains(&(from, to)) {
+					visited.insert((from, to));
+					face.push(from);
+					let adjacent = &vertices_vertices[to];
+					let i = adjacent.iter().position(|v| *v == from).unwrap();
+					let turn = adjacent[(i + adjacent.len() - 1) % adjacent.len()];
+					from = to;
+					to = turn;
+				}
+				let polygon: Vec<Vector> = face.iter().map(|v| self.vertices_coords[*v]).collect();
+				if signed_area(&polygon) <= EPSILON { continue; }
+				let n = polygon.len();
+				let (a, b) = (0..n).map(|i| (polygon[i], polygon[(i + 1) % n]))
+					.fold((polygon[0], polygon[0]), |longest, (a, b)|
+						if a.distance_to(b) > longest.0.distance_to(longest.1) { (a, b) } else { longest });
+				let inside = a.midpoint(b).add(b.subtract(a).rotate90().scale(1.0e-6));
+				if !self.on_paper(inside) { continue; }
+				faces.push(face);
+			}
+		}
+		self.faces_vertices = faces;
+	}
+}
+
+impl From<&ConvexPolygon> for Graph {
+	fn from (polygon: &ConvexPolygon) -> Graph {
+		Graph::from_points(polygon.vertices())
+	}
+}
+
+impl From<Rect> for Graph {
+	fn from (rect: Rect) -> Graph {
+		Graph::from(&ConvexPolygon::from(rect))
+	}
+}
+
+impl fmt::Debug for Graph {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Graph")
+			.field("vertices_coords", &self.vertices_coords)
+			.field("edges_vertices", &self.edges_vertices)
+			.field("edges_assignment", &self.edges_assignment)
+			.field("faces_vertices", &self.faces_vertices)
+			.finish()
+	}
+}
+
+fn sorted_pair (a: usize, b: usize) -> (usize, usize) {
+	if a < b { (a, b) } else { (b, a) }
+}
@@ -0,0 +1,344 @@
+// the stacking order of the faces of a folded crease pattern.
+// every pair of faces which overlap when folded has a variable: which of
+// the two is on top. the mountains and valleys decide the pairs which meet
+// at a crease, the rest follow from the rules of how paper can stack:
+// - taco-taco: two creases folded along the same line, with their faces
+//   on the same side, can't interleave.
+// - taco-tortilla: a face which a crease is folded across can't be
+//   between the crease's two faces.
+// - transitivity: three faces which overlap in one place can't be in a cycle.
+// the rules become clauses for a small SAT solver, unit propagation and
+// backtracking. the solutions are in the FOLD format's faceOrders:
+// (f, g, s), s is 1 if face f is above face g (on the side of g's normal),
+// or -1 if it is below. the normal of an unfolded face points towards the
+// viewer of the crease pattern.
+use std::fmt;
+use std::collections::HashMap;
+use math::Vector;
+use math::Segment;
+use math::EPSILON;
+use math::signed_area;
+use math::winding;
+use math::on_edge;
+use math::triangulate;
+use math::convex_intersection;
+use graph::Graph;
+use graph::Assignment;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Constraint {
+	// (edge) a mountain or valley sets the order of the edge's two faces
+	Crease(usize),
+	// (edge, edge) two creases folded along the same line
+	TacoTaco(usize, usize),
+	// (edge, face) a crease folded across the middle of a face
+	TacoTortilla(usize, usize),
+	// (face, face, face) three faces which overlap in one place
+	Transitivity(usize, usize, usize)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayerError {
+	// there must be one folded point for every vertex (expected, found)
+	VertexCount(usize, usize),
+	// (edge) a mountain or valley whose faces aren't folded over each
+	// other, or a flat edge whose faces are
+	CreaseMismatch(usize),
+	// no stacking exists. if the assignments of the creases alone lead to
+	// a contradiction, this is the constraint which they break.
+	Infeasible(Option<Constraint>)
+}
+
+impl fmt::Display for LayerError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			LayerError::VertexCount(expected, found) =>
+				write!(f, "expected {} folded vertices, found {}", expected, found),
+			LayerError::CreaseMismatch(edge) =>
+				write!(f, "edge {} is not folded the way it is assigned", edge),
+			LayerError::Infeasible(Some(constraint)) =>
+				write!(f, "the faces can't be stacked, {:?} is broken", constraint),
+			LayerError::Infeasible(None) => write!(f, "the faces can't be stacked")
+		}
+	}
+}
+
+impl ::std::error::Error for LayerError {}
+
+// the faceOrders of a FOLD frame, one (f, g, s) for every pair of overlapping faces
+pub type FaceOrders = Vec<(usize, usize, i8)>;
+
+// (variable, value)
+type Literal = (usize, bool);
+
+// a crease whose two faces are folded on top of each other
+struct Taco {
+	edge: usize,
+	faces: [usize; 2],
+	segment: Segment
+}
+
+struct Problem {
+	// the pair of faces of each variable, the smaller index first.
+	// the variable is true if the first face is above the second.
+	pairs: Vec<(usize, usize)>,
+	variables: HashMap<(usize, usize), usize>,
+	clauses: Vec<(Vec<Literal>, Constraint)>,
+	flipped: Vec<bool>
+}
+
+impl Problem {
+	// the literal which says face a is above face b, if they overlap
+	fn above (&self, a: usize, b: usize) -> Option<Literal> {
+		let key = if a < b { (a, b) } else { (b, a) };
+		self.variables.get(&key).map(|v| (*v, a < b))
+	}
+	fn face_orders (&self, values: &[bool]) -> FaceOrders {
+		self.pairs.iter().zip(values.iter())
+			.map(|((f, g), above)| (*f, *g, if *above != self.flipped[*g] { 1 } else { -1 }))
+			.collect()
+	}
+}
+
+fn area (polygon: &[Vector]) -> f64 {
+	if polygon.len() < 3 { 0.0 } else { signed_area(polygon) }
+}
+
+// do two faces, as triangles, overlap by more than a sliver
+fn overlap (a: &[[Vector; 3]], b: &[[Vector; 3]]) -> bool {
+	a.iter().any(|s| b.iter().any(|t| area(&convex_intersection(s, t)) > EPSILON))
+}
+
+fn overlap3 (a: &[[Vector; 3]], b: &[[Vector; 3]], c: &[[Vector; 3]]) -> bool {
+	a.iter().any(|s| b.iter().any(|t| {
+		let st = convex_intersection(s, t);
+		area(&st) > EPSILON && c.iter().any(|u| area(&convex_intersection(&st, u)) > EPSILON)
+	}))
+}
+
+// every pair of faces which overlap, the smaller index first
+fn overlapping_pairs (triangles: &[Vec<[Vector; 3]>]) -> Vec<(usize, usize)> {
+	(0..triangles.len())
+		.flat_map(|f| ((f + 1)..triangles.len()).map(move |g| (f, g)))
+		.filter(|(f, g)| overlap(&triangles[*f], &triangles[*g]))
+		.collect()
+}
+
+// the pairs of faces which overlap when the crease pattern is folded
+pub(crate) fn overlapping_faces (graph: &Graph, folded: &[Vector]) -> Vec<(usize, usize)> {
+	let triangles: Vec<Vec<[Vector; 3]>> = graph.faces_vertices.iter()
+		.map(|face| triangulate(&face.iter().map(|v| folded[*v]).collect::<Vec<Vector>>()))
+		.collect();
+	return overlapping_pairs(&triangles);
+}
+
+// does the segment pass through the inside of the polygon, not only along its edge
+fn crosses_interior (segment: Segment, polygon: &[Vector]) -> bool {
+	let vector = segment.b.subtract(segment.a);
+	let n = polygon.len();
+	let mut ts = vec![0.0, 1.0];
+	for i in 0..n {
+		let (p, q) = (polygon[i], polygon[(i + 1) % n]);
+		let edge = q.subtract(p);
+		let det = vector.determinant(edge);
+		if det.abs() < EPSILON { continue; }
+		let offset = p.subtract(segment.a);
+		let t = offset.determinant(edge) / det;
+		let s = offset.determinant(vector) / det;
+		if t > 0.0 && t < 1.0 && s > -EPSILON && s < 1.0 + EPSILON { ts.push(t); }
+	}
+	ts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+	return ts.windows(2)
+		.filter(|t| t[1] - t[0] > EPSILON)
+		.map(|t| segment.a.add(vector.scale((t[0] + t[1]) / 2.0)))
+		.any(|p| winding(polygon, p) != 0
+			&& !(0..n).any(|i| on_edge(polygon[i], polygon[(i + 1) % n], p)));
+}
+
+// which side of the segment's line a point is on
+fn side (segment: Segment, p: Vector) -> f64 {
+	segment.b.subtract(segment.a).determinant(p.subtract(segment.a))
+}
+
+// two tacos folded along the same line, overlapping by more than a point
+fn collinear_overlap (a: Segment, b: Segment) -> bool {
+	let vector = a.b.subtract(a.a);
+	let length = vector.magnitude();
+	if length < EPSILON { return false; }
+	if side(a, b.a).abs() / length > EPSILON || side(a, b.b).abs() / length > EPSILON { return false; }
+	let t = |p: Vector| p.subtract(a.a).dot(vector) / length;
+	let (b0, b1) = (t(b.a).min(t(b.b)), t(b.a).max(t(b.b)));
+	return b1.min(length) - b0.max(0.0) > EPSILON;
+}
+
+// the faces of two tacos are interleaved if exactly one of the second taco's
+// faces is between the first taco's faces. positions are bottom to top.
+fn interleaved (a: [usize; 2], b: [usize; 2]) -> bool {
+	let (low, high) = (a[0].min(a[1]), a[0].max(a[1]));
+	let between = |p: usize| p > low && p < high;
+	return between(b[0]) != between(b[1]);
+}
+
+fn build (graph: &Graph, folded: &[Vector]) -> Result<Problem, LayerError> {
+	if folded.len() != graph.vertices_coords.len() {
+		return Err(LayerError::VertexCount(graph.vertices_coords.len(), folded.len()));
+	}
+	let polygons: Vec<Vec<Vector>> = graph.faces_vertices.iter()
+		.map(|face| face.iter().map(|v| folded[*v]).collect())
+		.collect();
+	let flipped: Vec<bool> = polygons.iter().map(|p| signed_area(p) < 0.0).collect();
+	let triangles: Vec<Vec<[Vector; 3]>> = polygons.iter().map(|p| triangulate(p)).collect();
+	// a point inside each face, in its folded position
+	let interior: Vec<Option<Vector>> = triangles.iter()
+		.map(|t| t.first().map(|[a, b, c]| a.add(*b).add(*c).scale(1.0 / 3.0)))
+		.collect();
+	let pairs = overlapping_pairs(&triangles);
+	let variables = pairs.iter().enumerate().map(|(i, pair)| (*pair, i)).collect();
+	let mut problem = Problem { pairs, variables, clauses: vec![], flipped };
+	// the creases. a valley folds each face up towards the other's normal,
+	// a mountain folds it away.
+	let mut tacos = vec![];
+	for (e, faces) in graph.edges_faces().iter().enumerate() {
+		if faces.len() != 2 { continue; }
+		let (f, g) = (faces[0], faces[1]);
+		let is_folded = problem.flipped[f] != problem.flipped[g];
+		let assignment = graph.edges_assignment[e];
+		match assignment {
+			Assignment::Mountain | Assignment::Valley => {
+				if !is_folded { return Err(LayerError::CreaseMismatch(e)); }
+				let upward = (assignment == Assignment::Valley) != problem.flipped[f];
+				if let Some(literal) = problem.above(g, f) {
+					let literal = if upward { literal } else { (literal.0, !literal.1) };
+					problem.clauses.push((vec![literal], Constraint::Crease(e)));
+				}
+			},
+			Assignment::Flat if is_folded => return Err(LayerError::CreaseMismatch(e)),
+			_ => ()
+		}
+		if is_folded {
+			let [a, b] = graph.edges_vertices[e];
+			tacos.push(Taco { edge: e, faces: [f, g], segment: Segment { a: folded[a], b: folded[b] } });
+		}
+	}
+	// taco-tortilla: the face h is above both of the taco's faces, or below both
+	for taco in tacos.iter() {
+		let [f, g] = taco.faces;
+		for (h, polygon) in polygons.iter().enumerate() {
+			if h == f || h == g { continue; }
+			if let (Some(x), Some(y)) = (problem.above(h, f), problem.above(h, g)) {
+				if !crosses_interior(taco.segment, polygon) { continue; }
+				let constraint = Constraint::TacoTortilla(taco.edge, h);
+				problem.clauses.push((vec![(x.0, !x.1), y], constraint));
+				problem.clauses.push((vec![x, (y.0, !y.1)], constraint));
+			}
+		}
+	}
+	// taco-taco: every stacking of the four faces which interleaves the
+	// two tacos is ruled out
+	for (i, a) in tacos.iter().enumerate() {
+		for b in tacos.iter().skip(i + 1) {
+			let faces = [a.faces[0], a.faces[1], b.faces[0], b.faces[1]];
+			if (0..4).any(|j| (0..j).any(|k| faces[j] == faces[k])) { continue; }
+			if !collinear_overlap(a.segment, b.segment) { continue; }
+			let (side_a, side_b) = match (interior[a.faces[0]], interior[b.faces[0]]) {
+				(Some(p), Some(q)) => (side(a.segment, p), side(a.segment, q)),
+				_ => continue
+			};
+			if (side_a > 0.0) != (side_b > 0.0) { continue; }
+			let pairs: Vec<(usize, usize)> = (0..4).flat_map(|j| ((j + 1)..4).map(move |k| (j, k))).collect();
+			let literals: Vec<Literal> = match pairs.iter()
+				.map(|(j, k)| problem.above(faces[*j], faces[*k]))
+				.collect::<Option<Vec<Literal>>>() {
+				Some(literals) => literals,
+				None => continue
+			};
+			for state in 0..(1 << pairs.len()) {
+				// the position of each face is the number of faces below it,
+				// if these are all different the state is a stacking
+				let mut positions = [0; 4];
+				for (n, (j, k)) in pairs.iter().enumerate() {
+					if state & (1 << n) != 0 { positions[*j] += 1; } else { positions[*k] += 1; }
+				}
+				if (0..4).any(|j| (0..j).any(|k| positions[j] == positions[k])) { continue; }
+				if !interleaved([positions[0], positions[1]], [positions[2], positions[3]]) { continue; }
+				let clause = literals.iter().enumerate()
+					.map(|(n, (v, value))| (*v, if state & (1 << n) != 0 { !value } else { *value }))
+					.collect();
+				problem.clauses.push((clause, Constraint::TacoTaco(a.edge, b.edge)));
+			}
+		}
+	}
+	// transitivity: not a above b above c above a, or the other way around
+	for (a, b) in problem.pairs.clone() {
+		for c in (b + 1)..polygons.len() {
+			if let (Some(ab), Some(bc), Some(ca)) = (problem.above(a, b), problem.above(b, c), problem.above(c, a)) {
+				if !overlap3(&triangles[a], &triangles[b], &triangles[c]) { continue; }
+				let constraint = Constraint::Transitivity(a, b, c);
+				problem.clauses.push((vec![(ab.0, !ab.1), (bc.0, !bc.1), (ca.0, !ca.1)], constraint));
+				problem.clauses.push((vec![ab, bc, ca], constraint));
+			}
+		}
+	}
+	return Ok(problem);
+}
+
+// assign every variable which is the last one left in a clause,
+// or return the constraint of a clause which can't be satisfied
+fn propagate (clauses: &[(Vec<Literal>, Constraint)], values: &mut [Option<bool>]) -> Result<(), Constraint> {
+	let mut changed = true;
+	while changed {
+		changed = false;
+		for (clause, constraint) in clauses.iter() {
+			if clause.iter().any(|(v, value)| values[*v] == Some(*value)) { continue; }
+			let mut open = clause.iter().filter(|(v, _)| values[*v].is_none());
+			match (open.next(), open.next()) {
+				(None, _) => return Err(*constraint),
+				(Some((v, value)), None) => {
+					values[*v] = Some(*value);
+					changed = true;
+				},
+				_ => ()
+			}
+		}
+	}
+	return Ok(());
+}
+
+fn search (
+	clauses: &[(Vec<Literal>, Constraint)],
+	mut values: Vec<Option<bool>>,
+	all: bool,
+	solutions: &mut Vec<Vec<bool>>
+) {
+	if propagate(clauses, &mut values).is_err() { return; }
+	match values.iter().position(|v| v.is_none()) {
+		None => solutions.push(values.iter().map(|v| v.unwrap()).collect()),
+		Some(i) => for value in [true, false].iter() {
+			if !all && !solutions.is_empty() { return; }
+			let mut next = values.clone();
+			next[i] = Some(*value);
+			search(clauses, next, all, solutions);
+		}
+	}
+}
+
+fn solutions (graph: &Graph, folded: &[Vector], all: bool) -> Result<Vec<FaceOrders>, LayerError> {
+	let problem = build(graph, folded)?;
+	let mut values = vec![None; problem.pairs.len()];
+	propagate(&problem.clauses, &mut values).map_err(|c| LayerError::Infeasible(Some(c)))?;
+	let mut solutions = vec![];
+	search(&problem.clauses, values, all, &mut solutions);
+	if solutions.is_empty() { return Err(LayerError::Infeasible(None)); }
+	return Ok(solutions.iter().map(|values| problem.face_orders(values)).collect());
+}
+
+// one stacking of the faces. folded has the folded position of every vertex.
+pub fn solve (graph: &Graph, folded: &[Vector]) -> Result<FaceOrders, LayerError> {
+	solutions(graph, folded, false).map(|mut solutions| solutions.remove(0))
+}
+
+// every stacking of the faces. faces which aren't constrained by any
+// others multiply the number of solutions.
+pub fn solve_all (graph: &Graph, folded: &[Vector]) -> Result<Vec<FaceOrders>, LayerError> {
+	solutions(graph, folded, true)
+}
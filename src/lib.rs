@@ -1,5 +1,20 @@
+// the source uses explicit returns and field names in many places
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+#![allow(clippy::manual_range_contains)]
+
+extern crate serde_json;
+extern crate roxmltree;
+
 // export these under the module axioms::
 pub mod axioms;
+pub mod fold;
+pub mod svg;
+pub mod oripa;
+pub mod vertex;
+pub mod layer;
+pub mod folded;
+pub mod model;
+pub mod reference;
 
 // // export these under the top level
 // mod axioms;
@@ -13,10 +28,29 @@ pub mod axioms;
 
 // export these under the top level
 mod math;
+pub use math::polynomial;
+pub use math::exact;
+pub use math::predicates;
 pub use math::Vector;
 pub use math::Line;
 pub use math::Segment;
+pub use math::Ray;
 pub use math::Rect;
+pub use math::ConvexPolygon;
+pub use math::Polygon;
+pub use math::PolygonError;
+pub use math::Boundary;
+pub use math::Matrix;
+pub use math::Scalar;
+pub use math::Tolerance;
+pub use math::EPSILON;
+
+mod graph;
+pub use graph::Graph;
+pub use graph::Assignment;
+mod planarize;
+pub use planarize::planarize;
+pub use planarize::resolve_assignment;
 
 // additional static constructors
 
@@ -24,10 +58,26 @@ pub use math::Rect;
 // it's very important that the "sides" are Lines with normals
 // that point outwards. needed for the "contains" method.
 pub fn make_square () -> Rect {
+	make_rect(1.0, 1.0)
+}
+
+// a rect with one corner at the origin, sides ordered counter-clockwise
+// starting with the bottom: bottom, right, top, left.
+pub fn make_rect (width: f64, height: f64) -> Rect {
 	Rect { sides: [
-		Line { u: Vector { x: 0.0 , y: 1.0 }, d: 0.0 },
-		Line { u: Vector { x: 1.0 , y: 0.0 }, d: 1.0 },
-		Line { u: Vector { x: 0.0 , y: -1.0 }, d: -1.0 },
+		Line { u: Vector { x: 0.0 , y: -1.0 }, d: 0.0 },
+		Line { u: Vector { x: 1.0 , y: 0.0 }, d: width },
+		Line { u: Vector { x: 0.0 , y: 1.0 }, d: height },
 		Line { u: Vector { x: -1.0 , y: 0.0 }, d: 0.0 }
 	]}
 }
+
+// a regular polygon inscribed in a circle of this radius around the
+// origin, the first corner is on the +x axis.
+pub fn make_regular_polygon (sides: usize, radius: f64) -> Result<ConvexPolygon, PolygonError> {
+	let points: Vec<Vector> = (0..sides)
+		.map(|i| 2.0 * std::f64::consts::PI * (i as f64) / (sides as f64))
+		.map(|a| Vector { x: radius * a.cos(), y: radius * a.sin() })
+		.collect();
+	ConvexPolygon::from_points(&points)
+}
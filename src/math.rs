@@ -1,12 +1,34 @@
 mod line;
 mod vector;
 mod segment;
+mod ray;
 mod rect;
+mod polygon;
+mod boundary;
+mod matrix;
+mod scalar;
+mod tolerance;
+pub mod polynomial;
+pub mod exact;
+pub mod predicates;
 
 pub use self::line::Line;
 pub use self::vector::Vector;
 pub use self::segment::Segment;
+pub use self::ray::Ray;
 pub use self::rect::Rect;
+pub use self::polygon::ConvexPolygon;
+pub use self::polygon::Polygon;
+pub use self::polygon::PolygonError;
+pub use self::boundary::Boundary;
+pub use self::matrix::Matrix;
+pub use self::scalar::Scalar;
+pub use self::tolerance::Tolerance;
+pub(crate) use self::polygon::signed_area;
+pub(crate) use self::polygon::winding;
+pub(crate) use self::polygon::on_edge;
+pub(crate) use self::polygon::triangulate;
+pub(crate) use self::polygon::convex_intersection;
 
 mod common;
 pub use self::common::EPSILON;
@@ -0,0 +1,118 @@
+use std::cmp::Ordering;
+use super::vector::Vector;
+use super::line::Line;
+use super::segment::Segment;
+use super::scalar::Scalar;
+use super::tolerance::Tolerance;
+
+// anything that can act as the paper: it needs to be able to say if a point
+// is on the paper, and to chop an infinite line into the piece on the paper.
+pub trait Boundary<T: Scalar = f64> {
+	fn contains (&self, p: Vector<T>) -> bool;
+	// contains, with points this close to the edge counted as on the paper
+	fn contains_with (&self, p: Vector<T>, _tolerance: &Tolerance<T>) -> bool {
+		self.contains(p)
+	}
+	// @returns a tuple: true/false if clip is possible and the segment.
+	fn clip (&self, l: Line<T>) -> (bool, Segment<T>);
+	// clip, with pieces shorter than the tolerance not counted
+	fn clip_with (&self, l: Line<T>, _tolerance: &Tolerance<T>) -> (bool, Segment<T>) {
+		self.clip(l)
+	}
+	// every piece of the line that lies on the paper. a convex boundary
+	// only ever has one, but a boundary with notches or holes can have many.
+	fn clip_all (&self, l: Line<T>) -> Vec<Segment<T>> {
+		let (success, segment) = self.clip(l);
+		if success { vec![segment] } else { vec![] }
+	}
+	fn clip_all_with (&self, l: Line<T>, tolerance: &Tolerance<T>) -> Vec<Segment<T>> {
+		let (success, segment) = self.clip_with(l, tolerance);
+		if success { vec![segment] } else { vec![] }
+	}
+	// the outline of the paper: the outside ring (counter-clockwise)
+	// followed by the rings of any holes (clockwise).
+	fn rings (&self) -> Vec<Vec<Vector<T>>>;
+}
+
+// allow the axioms to be called with a borrowed boundary, so that
+// non-Copy boundaries (polygons) don't have to be cloned for every call
+impl<T: Scalar, B: Boundary<T> + ?Sized> Boundary<T> for &B {
+	fn contains (&self, p: Vector<T>) -> bool { (**self).contains(p) }
+	fn contains_with (&self, p: Vector<T>, tolerance: &Tolerance<T>) -> bool {
+		(**self).contains_with(p, tolerance)
+	}
+	fn clip (&self, l: Line<T>) -> (bool, Segment<T>) { (**self).clip(l) }
+	fn clip_with (&self, l: Line<T>, tolerance: &Tolerance<T>) -> (bool, Segment<T>) {
+		(**self).clip_with(l, tolerance)
+	}
+	fn clip_all (&self, l: Line<T>) -> Vec<Segment<T>> { (**self).clip_all(l) }
+	fn clip_all_with (&self, l: Line<T>, tolerance: &Tolerance<T>) -> Vec<Segment<T>> {
+		(**self).clip_all_with(l, tolerance)
+	}
+	fn rings (&self) -> Vec<Vec<Vector<T>>> { (**self).rings() }
+}
+
+pub const NULL_SEGMENT: Segment = Segment {
+	a: Vector {x:0.0, y:0.0},
+	b: Vector {x:0.0, y:0.0}
+};
+
+fn null_segment<T: Scalar> () -> Segment<T> {
+	let zero = Vector { x: T::zero(), y: T::zero() };
+	Segment { a: zero.clone(), b: zero }
+}
+
+// the following work on any convex polygon described by lines
+// whose normals point outwards. the point is inside if it is
+// behind (or on) every one of the sides, or outside by no more than
+// the tolerance. which side it's on comes from the robust predicate.
+// a point that can't be compared (NaN) is outside.
+pub fn convex_contains<T: Scalar> (sides: &[Line<T>], p: Vector<T>, tolerance: &Tolerance<T>) -> bool {
+	sides.iter().all(|side| {
+		let distance = p.dot(side.u.clone()) - side.d.clone();
+		match T::side(side, &p) {
+			Ordering::Greater => distance <= tolerance.distance,
+			Ordering::Equal => distance.partial_cmp(&T::zero()).is_some(),
+			Ordering::Less => true
+		}
+	})
+}
+
+// the polygon here is its corners, in order. the line crosses the polygon
+// where the corners change from one side of it to the other, so a line
+// which misses the polygon (by any amount) can't be clipped, and one which
+// only touches a corner makes a degenerate segment.
+pub fn convex_clip<T: Scalar> (ring: &[Vector<T>], l: Line<T>, tolerance: &Tolerance<T>) -> (bool, Segment<T>) {
+	let n = ring.len();
+	let sides: Vec<Ordering> = ring.iter().map(|p| T::side(&l, p)).collect();
+	let distance = |p: &Vector<T>| (p.dot(l.u.clone()) - l.d.clone()).abs();
+	let mut results: Vec<Vector<T>> = vec![];
+	for (i, a) in ring.iter().enumerate() {
+		let (j, b) = ((i + 1) % n, &ring[(i + 1) % n]);
+		if sides[i] == Ordering::Equal { results.push(a.clone()); }
+		if sides[i] == sides[j] || sides[i] == Ordering::Equal || sides[j] == Ordering::Equal {
+			continue;
+		}
+		// the edge crosses the line, find where by how far each end is from it
+		let (da, db) = (distance(a), distance(b));
+		let total = da.clone() + db;
+		let t = if total > T::zero() { da / total } else { T::one() / (T::one() + T::one()) };
+		results.push(a.add(b.subtract(a.clone()).scale(t)));
+	}
+	if results.len() < 2 { return (false, null_segment()); }
+	// sort intersection points along line
+	let origin = l.u.scale(l.d.clone());
+	let vector = l.u.rotate90();
+	let ts: Vec<T> = results.iter()
+		.map(|pt| pt.subtract(origin.clone()).dot(vector.clone()))
+		.collect();
+	// get the min and max, construct a segment between them
+	let min = ts.iter().fold(&ts[0], |a, b| if b < a {b} else {a}).clone();
+	let max = ts.iter().fold(&ts[0], |a, b| if b > a {b} else {a}).clone();
+	// if the two points are the same the segment is degenerate
+	if tolerance.zero(max.clone() - min.clone()) { return (false, null_segment()); }
+	return (true, Segment {
+		a: origin.add(vector.scale(min)),
+		b: origin.add(vector.scale(max))
+	});
+}
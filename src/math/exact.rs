@@ -0,0 +1,575 @@
+// exact numbers, for constructions that must not drift. a Rational is a
+// fraction of integers of any size. a Number is a constructible number, a
+// rational or a nested square root, like 1/3 or sqrt(2) - 1 or
+// sqrt(2 + sqrt(3)), which covers everything axioms 1 to 5 and 7 can make.
+// axiom 6 can need the roots of a general cubic, which can't be written with
+// square roots, those are not represented. axiom 6 then returns an error,
+// AxiomError::NotRepresentable, rather than lines from float roots.
+//
+// a number is a + b sqrt(r), where a and b (and the radicand r) are numbers
+// built only from older square roots. every square root is given an id when
+// it's made, the newest one is always on the outside. comparing numbers is
+// exact, the sign of a + b sqrt(r) is found by comparing a^2 to b^2 r.
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex, Weak};
+
+// a natural number of any size, 32 bit limbs, the least significant first,
+// with no zero limbs at the end
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct Natural(Vec<u32>);
+
+impl Natural {
+	fn zero () -> Natural { Natural(vec![]) }
+	fn from_u64 (n: u64) -> Natural { Natural(vec![n as u32, (n >> 32) as u32]).trim() }
+	fn is_zero (&self) -> bool { self.0.is_empty() }
+	fn is_one (&self) -> bool { self.0.len() == 1 && self.0[0] == 1 }
+	fn trim (mut self) -> Natural {
+		while self.0.last() == Some(&0) { self.0.pop(); }
+		self
+	}
+	fn bits (&self) -> usize {
+		match self.0.last() {
+			Some(top) => self.0.len() * 32 - top.leading_zeros() as usize,
+			None => 0
+		}
+	}
+	fn bit (&self, i: usize) -> bool { (self.0[i / 32] >> (i % 32)) & 1 == 1 }
+	fn compare (&self, n: &Natural) -> Ordering {
+		if self.0.len() != n.0.len() { return self.0.len().cmp(&n.0.len()); }
+		for (a, b) in self.0.iter().rev().zip(n.0.iter().rev()) {
+			if a != b { return a.cmp(b); }
+		}
+		return Ordering::Equal;
+	}
+	fn add (&self, n: &Natural) -> Natural {
+		let mut limbs = Vec::with_capacity(self.0.len().max(n.0.len()) + 1);
+		let mut carry = 0u64;
+		for i in 0..self.0.len().max(n.0.len()) {
+			let sum = u64::from(*self.0.get(i).unwrap_or(&0)) + u64::from(*n.0.get(i).unwrap_or(&0)) + carry;
+			limbs.push(sum as u32);
+			carry = sum >> 32;
+		}
+		limbs.push(carry as u32);
+		Natural(limbs).trim()
+	}
+	// self must not be smaller than n
+	fn sub (&self, n: &Natural) -> Natural {
+		let mut limbs = Vec::with_capacity(self.0.len());
+		let mut borrow = 0i64;
+		for (i, a) in self.0.iter().enumerate() {
+			let mut difference = i64::from(*a) - i64::from(*n.0.get(i).unwrap_or(&0)) - borrow;
+			borrow = if difference < 0 { difference += 1 << 32; 1 } else { 0 };
+			limbs.push(difference as u32);
+		}
+		Natural(limbs).trim()
+	}
+	fn mul (&self, n: &Natural) -> Natural {
+		if self.is_zero() || n.is_zero() { return Natural::zero(); }
+		let mut limbs = vec![0u32; self.0.len() + n.0.len()];
+		for (i, a) in self.0.iter().enumerate() {
+			let mut carry = 0u64;
+			for (j, b) in n.0.iter().enumerate() {
+				let product = u64::from(*a) * u64::from(*b) + u64::from(limbs[i + j]) + carry;
+				limbs[i + j] = product as u32;
+				carry = product >> 32;
+			}
+			limbs[i + n.0.len()] = carry as u32;
+		}
+		Natural(limbs).trim()
+	}
+	fn shift_left (&self, bits: usize) -> Natural {
+		if self.is_zero() { return Natural::zero(); }
+		let mut limbs = vec![0u32; bits / 32];
+		let shift = bits % 32;
+		let mut carry = 0u32;
+		for a in self.0.iter() {
+			limbs.push((a << shift) | carry);
+			carry = if shift == 0 { 0 } else { a >> (32 - shift) };
+		}
+		limbs.push(carry);
+		Natural(limbs).trim()
+	}
+	fn shift_right (&self, bits: usize) -> Natural {
+		let (skip, shift) = (bits / 32, bits % 32);
+		if skip >= self.0.len() { return Natural::zero(); }
+		let limbs = (skip..self.0.len()).map(|i| {
+			let high = if shift == 0 { 0 } else { self.0.get(i + 1).map_or(0, |h| h << (32 - shift)) };
+			(self.0[i] >> shift) | high
+		}).collect();
+		Natural(limbs).trim()
+	}
+	// (quotient, remainder), long division one bit at a time
+	fn divide (&self, n: &Natural) -> (Natural, Natural) {
+		assert!(!n.is_zero(), "division by zero");
+		if n.0.len() == 1 {
+			let d = u64::from(n.0[0]);
+			let mut limbs = vec![0u32; self.0.len()];
+			let mut remainder = 0u64;
+			for i in (0..self.0.len()).rev() {
+				let current = (remainder << 32) | u64::from(self.0[i]);
+				limbs[i] = (current / d) as u32;
+				remainder = current % d;
+			}
+			return (Natural(limbs).trim(), Natural::from_u64(remainder));
+		}
+		let mut quotient = vec![0u32; self.0.len()];
+		let mut remainder = Natural::zero();
+		for i in (0..self.bits()).rev() {
+			remainder = remainder.shift_left(1);
+			if self.bit(i) {
+				if remainder.is_zero() { remainder = Natural(vec![1]); } else { remainder.0[0] |= 1; }
+			}
+			if remainder.compare(n) != Ordering::Less {
+				remainder = remainder.sub(n);
+				quotient[i / 32] |= 1 << (i % 32);
+			}
+		}
+		(Natural(quotient).trim(), remainder)
+	}
+	fn gcd (&self, n: &Natural) -> Natural {
+		let (mut a, mut b) = (self.clone(), n.clone());
+		while !b.is_zero() {
+			let r = a.divide(&b).1;
+			a = b;
+			b = r;
+		}
+		a
+	}
+	// the largest integer whose square is not more than this
+	fn sqrt (&self) -> Natural {
+		if self.is_zero() { return Natural::zero(); }
+		let mut x = Natural(vec![1]).shift_left(self.bits().div_ceil(2));
+		loop {
+			let y = x.add(&self.divide(&x).0).shift_right(1);
+			if y.compare(&x) != Ordering::Less { return x; }
+			x = y;
+		}
+	}
+}
+
+impl fmt::Display for Natural {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.is_zero() { return write!(f, "0"); }
+		let billion = Natural::from_u64(1_000_000_000);
+		let mut chunks = vec![];
+		let mut n = self.clone();
+		while !n.is_zero() {
+			let (q, r) = n.divide(&billion);
+			chunks.push(r.0.first().cloned().unwrap_or(0));
+			n = q;
+		}
+		write!(f, "{}", chunks.pop().unwrap())?;
+		for chunk in chunks.iter().rev() { write!(f, "{:09}", chunk)?; }
+		Ok(())
+	}
+}
+
+// a fraction in lowest terms with a positive denominator. zero is never negative.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+	negative: bool,
+	numerator: Natural,
+	denominator: Natural
+}
+
+impl Rational {
+	fn make (negative: bool, numerator: Natural, denominator: Natural) -> Rational {
+		assert!(!denominator.is_zero(), "division by zero");
+		let divisor = numerator.gcd(&denominator);
+		let (numerator, denominator) = if divisor.is_zero() || divisor.is_one() { (numerator, denominator) }
+			else { (numerator.divide(&divisor).0, denominator.divide(&divisor).0) };
+		let denominator = if numerator.is_zero() { Natural(vec![1]) } else { denominator };
+		Rational { negative: negative && !numerator.is_zero(), numerator, denominator }
+	}
+	pub fn new (numerator: i64, denominator: i64) -> Rational {
+		Rational::make(
+			(numerator < 0) != (denominator < 0),
+			Natural::from_u64(numerator.unsigned_abs()),
+			Natural::from_u64(denominator.unsigned_abs()))
+	}
+	pub fn integer (n: i64) -> Rational { Rational::new(n, 1) }
+	pub fn zero () -> Rational { Rational::integer(0) }
+	pub fn one () -> Rational { Rational::integer(1) }
+	// every finite float is exactly a fraction with a power of two below
+	pub fn from_f64 (n: f64) -> Option<Rational> {
+		if !n.is_finite() { return None; }
+		let bits = n.to_bits();
+		let negative = bits >> 63 == 1;
+		let exponent = ((bits >> 52) & 0x7ff) as i64;
+		let fraction = bits & 0xf_ffff_ffff_ffff;
+		let (mantissa, exponent) = if exponent == 0 { (fraction, -1074) }
+			else { (fraction | (1 << 52), exponent - 1075) };
+		let mantissa = Natural::from_u64(mantissa);
+		let one = Natural(vec![1]);
+		return Some(if exponent >= 0 {
+			Rational::make(negative, mantissa.shift_left(exponent as usize), one)
+		} else {
+			Rational::make(negative, mantissa, one.shift_left((-exponent) as usize))
+		});
+	}
+	pub fn is_zero (&self) -> bool { self.numerator.is_zero() }
+	pub fn is_integer (&self) -> bool { self.denominator.is_one() }
+	pub fn signum (&self) -> i32 {
+		if self.is_zero() { 0 } else if self.negative { -1 } else { 1 }
+	}
+	pub fn abs (&self) -> Rational { Rational { negative: false, ..self.clone() } }
+	pub fn inverse (&self) -> Rational {
+		Rational::make(self.negative, self.denominator.clone(), self.numerator.clone())
+	}
+	// the exact square root, if this is the square of a fraction
+	pub fn sqrt (&self) -> Option<Rational> {
+		if self.negative { return None; }
+		let n = self.numerator.sqrt();
+		let d = self.denominator.sqrt();
+		if n.mul(&n) != self.numerator || d.mul(&d) != self.denominator { return None; }
+		return Some(Rational::make(false, n, d));
+	}
+	// the nearest float (ties to even). the quotient is worked out to 55 or
+	// 56 bits, with a sticky bit for anything left over, then rounded to
+	// the 53 bits of a float, or fewer if it's subnormal.
+	pub fn to_f64 (&self) -> f64 {
+		if self.is_zero() { return 0.0; }
+		let sign = if self.negative { 1u64 << 63 } else { 0 };
+		let s = 55 - (self.numerator.bits() as i64 - self.denominator.bits() as i64);
+		let (n, d) = if s >= 0 { (self.numerator.shift_left(s as usize), self.denominator.clone()) }
+			else { (self.numerator.clone(), self.denominator.shift_left((-s) as usize)) };
+		let (q, remainder) = n.divide(&d);
+		let sticky = !remainder.is_zero();
+		let q = q.0.iter().rev().fold(0u64, |sum, limb| (sum << 32) | u64::from(*limb));
+		// the value is q 2^-s, its top bit is 2^(k - s)
+		let k = 63 - i64::from(q.leading_zeros());
+		// how many bits of q are rounded away, normal or subnormal
+		let drop = (k - 52).max(s - 1074);
+		if drop > 56 { return f64::from_bits(sign); }
+		let mut mantissa = q >> drop;
+		let rest = q & ((1u64 << drop) - 1);
+		let half = 1u64 << (drop - 1);
+		if rest > half || (rest == half && (sticky || mantissa & 1 == 1)) { mantissa += 1; }
+		let mut exponent = drop - s;
+		if mantissa == 1 << 53 { mantissa >>= 1; exponent += 1; }
+		if mantissa < 1 << 52 { return f64::from_bits(sign | mantissa); }
+		let biased = exponent + 52 + 1023;
+		if biased > 2046 { return f64::from_bits(sign | f64::INFINITY.to_bits()); }
+		return f64::from_bits(sign | ((biased as u64) << 52) | (mantissa - (1 << 52)));
+	}
+	fn add_signed (&self, n: &Rational, negate: bool) -> Rational {
+		let a = self.numerator.mul(&n.denominator);
+		let b = n.numerator.mul(&self.denominator);
+		let denominator = self.denominator.mul(&n.denominator);
+		let n_negative = n.negative != negate;
+		if self.negative == n_negative {
+			return Rational::make(self.negative, a.add(&b), denominator);
+		}
+		match a.compare(&b) {
+			Ordering::Less => Rational::make(n_negative, b.sub(&a), denominator),
+			_ => Rational::make(self.negative, a.sub(&b), denominator)
+		}
+	}
+}
+
+impl Add for &Rational {
+	type Output = Rational;
+	fn add (self, n: &Rational) -> Rational { self.add_signed(n, false) }
+}
+
+impl Sub for &Rational {
+	type Output = Rational;
+	fn sub (self, n: &Rational) -> Rational { self.add_signed(n, true) }
+}
+
+impl Mul for &Rational {
+	type Output = Rational;
+	fn mul (self, n: &Rational) -> Rational {
+		Rational::make(self.negative != n.negative, self.numerator.mul(&n.numerator), self.denominator.mul(&n.denominator))
+	}
+}
+
+impl Div for &Rational {
+	type Output = Rational;
+	fn div (self, n: &Rational) -> Rational {
+		Rational::make(self.negative != n.negative, self.numerator.mul(&n.denominator), self.denominator.mul(&n.numerator))
+	}
+}
+
+impl Neg for &Rational {
+	type Output = Rational;
+	fn neg (self) -> Rational { Rational::make(!self.negative, self.numerator.clone(), self.denominator.clone()) }
+}
+
+impl Ord for Rational {
+	fn cmp (&self, n: &Rational) -> Ordering { (self - n).signum().cmp(&0) }
+}
+
+impl PartialOrd for Rational {
+	fn partial_cmp (&self, n: &Rational) -> Option<Ordering> { Some(self.cmp(n)) }
+}
+
+impl fmt::Display for Rational {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.negative { write!(f, "-")?; }
+		write!(f, "{}", self.numerator)?;
+		if !self.is_integer() { write!(f, "/{}", self.denominator)?; }
+		Ok(())
+	}
+}
+
+impl fmt::Debug for Rational {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self) }
+}
+
+// a square root which isn't in the field of the numbers before it
+struct Radical {
+	id: usize,
+	radicand: Number
+}
+
+static RADICALS_MADE: AtomicUsize = AtomicUsize::new(0);
+
+// the square roots which are in use, so the same root isn't made twice
+fn radicals () -> &'static Mutex<Vec<Weak<Radical>>> {
+	static RADICALS: Mutex<Vec<Weak<Radical>>> = Mutex::new(Vec::new());
+	&RADICALS
+}
+
+#[derive(Clone)]
+enum Value {
+	Rational(Rational),
+	// a + b sqrt(radical), a and b only use older radicals and b isn't 0
+	Extension(Box<Number>, Box<Number>, Arc<Radical>)
+}
+
+#[derive(Clone)]
+pub struct Number(Value);
+
+impl Number {
+	pub fn zero () -> Number { Number::from(Rational::zero()) }
+	pub fn one () -> Number { Number::from(Rational::one()) }
+	pub fn integer (n: i64) -> Number { Number::from(Rational::integer(n)) }
+	pub fn fraction (numerator: i64, denominator: i64) -> Number { Number::from(Rational::new(numerator, denominator)) }
+	pub fn from_f64 (n: f64) -> Option<Number> { Rational::from_f64(n).map(Number::from) }
+	// the newest square root in this number
+	fn top (&self) -> Option<&Arc<Radical>> {
+		match self.0 {
+			Value::Rational(_) => None,
+			Value::Extension(_, _, ref r) => Some(r)
+		}
+	}
+	// (a, b) where this is a + b sqrt(r)
+	fn parts (&self, r: &Arc<Radical>) -> (Number, Number) {
+		match self.0 {
+			Value::Extension(ref a, ref b, ref s) if s.id == r.id => ((**a).clone(), (**b).clone()),
+			_ => (self.clone(), Number::zero())
+		}
+	}
+	fn make (a: Number, b: Number, r: &Arc<Radical>) -> Number {
+		if let Value::Rational(ref q) = b.0 {
+			if q.is_zero() { return a; }
+		}
+		Number(Value::Extension(Box::new(a), Box::new(b), r.clone()))
+	}
+	// the newer of the newest square roots of two numbers
+	fn newest (&self, n: &Number) -> Option<Arc<Radical>> {
+		match (self.top(), n.top()) {
+			(Some(a), Some(b)) => Some(if a.id >= b.id { a.clone() } else { b.clone() }),
+			(Some(a), None) => Some(a.clone()),
+			(None, Some(b)) => Some(b.clone()),
+			(None, None) => None
+		}
+	}
+	// -1, 0 or 1, exactly
+	pub fn signum (&self) -> i32 {
+		match self.0 {
+			Value::Rational(ref q) => q.signum(),
+			Value::Extension(ref a, ref b, ref r) => {
+				let (sa, sb) = (a.signum(), b.signum());
+				if sb == 0 || sa == sb { return sa; }
+				if sa == 0 { return sb; }
+				// opposite signs, the larger of a^2 and b^2 r wins
+				let n = &(&**a * &**a) - &(&(&**b * &**b) * &r.radicand);
+				return if sa > 0 { n.signum() } else { -n.signum() };
+			}
+		}
+	}
+	pub fn is_zero (&self) -> bool { self.signum() == 0 }
+	pub fn abs (&self) -> Number { if self.signum() < 0 { -self } else { self.clone() } }
+	pub fn inverse (&self) -> Number {
+		match self.0 {
+			Value::Rational(ref q) => Number::from(q.inverse()),
+			Value::Extension(ref a, ref b, ref r) => {
+				// 1 / (a + b sqrt(r)) = (a - b sqrt(r)) / (a^2 - b^2 r)
+				let n = &(&**a * &**a) - &(&(&**b * &**b) * &r.radicand);
+				if n.is_zero() {
+					// a = b sqrt(r) (the other way round is zero), this is 2a
+					assert!(a.signum() == b.signum(), "division by zero");
+					return (&**a + &**a).inverse();
+				}
+				let n = n.inverse();
+				Number::make(&**a * &n, -&(&**b * &n), r)
+			}
+		}
+	}
+	// the square root, if it's already in the field of this number
+	fn sqrt_in_field (&self) -> Option<Number> {
+		match self.0 {
+			Value::Rational(ref q) => q.sqrt().map(Number::from),
+			Value::Extension(ref a, ref b, ref r) => {
+				// (c + e sqrt(r))^2 = c^2 + e^2 r + 2ce sqrt(r), so c^2 is (a +- s) / 2
+				// where s^2 = a^2 - b^2 r
+				let s = (&(&**a * &**a) - &(&(&**b * &**b) * &r.radicand)).sqrt_in_field()?;
+				let half = Number::fraction(1, 2);
+				for t in [&(&**a + &s) * &half, &(&**a - &s) * &half].iter() {
+					if t.signum() <= 0 { continue; }
+					if let Some(c) = t.sqrt_in_field() {
+						let e = &**b / &(&c + &c);
+						// -c - e sqrt(r) squares to the same, keep the positive one
+						let root = Number::make(c, e, r);
+						return Some(if root.signum() < 0 { -&root } else { root });
+					}
+				}
+				return None;
+			}
+		}
+	}
+	// the exact square root, None for a negative number
+	pub fn sqrt (&self) -> Option<Number> {
+		match self.signum() {
+			-1 => return None,
+			0 => return Some(Number::zero()),
+			_ => ()
+		}
+		if let Some(root) = self.sqrt_in_field() { return Some(root); }
+		let mut list = radicals().lock().unwrap();
+		list.retain(|r| r.strong_count() > 0);
+		// a multiple of a root already made, sqrt(8) is 2 sqrt(2)
+		for radical in list.iter().filter_map(|r| r.upgrade()) {
+			if let Some(c) = (self / &radical.radicand).sqrt_in_field() {
+				return Some(&c * &Number::make(Number::zero(), Number::one(), &radical));
+			}
+		}
+		let radical = Arc::new(Radical {
+			id: RADICALS_MADE.fetch_add(1, AtomicOrdering::SeqCst),
+			radicand: self.clone()
+		});
+		list.push(Arc::downgrade(&radical));
+		return Some(Number::make(Number::zero(), Number::one(), &radical));
+	}
+	pub fn to_f64 (&self) -> f64 {
+		match self.0 {
+			Value::Rational(ref q) => q.to_f64(),
+			Value::Extension(ref a, ref b, ref r) => a.to_f64() + b.to_f64() * r.radicand.to_f64().sqrt()
+		}
+	}
+}
+
+impl Add for &Number {
+	type Output = Number;
+	fn add (self, n: &Number) -> Number {
+		match self.newest(n) {
+			None => match (&self.0, &n.0) {
+				(Value::Rational(a), Value::Rational(b)) => Number::from(a + b),
+				_ => unreachable!()
+			},
+			Some(r) => {
+				let (a, b) = self.parts(&r);
+				let (c, d) = n.parts(&r);
+				Number::make(&a + &c, &b + &d, &r)
+			}
+		}
+	}
+}
+
+impl Neg for &Number {
+	type Output = Number;
+	fn neg (self) -> Number {
+		match self.0 {
+			Value::Rational(ref q) => Number::from(-q),
+			Value::Extension(ref a, ref b, ref r) => Number::make(-&**a, -&**b, r)
+		}
+	}
+}
+
+impl Sub for &Number {
+	type Output = Number;
+	fn sub (self, n: &Number) -> Number { self + &(-n) }
+}
+
+impl Mul for &Number {
+	type Output = Number;
+	fn mul (self, n: &Number) -> Number {
+		match self.newest(n) {
+			None => match (&self.0, &n.0) {
+				(Value::Rational(a), Value::Rational(b)) => Number::from(a * b),
+				_ => unreachable!()
+			},
+			Some(r) => {
+				// (a + b sqrt(r))(c + d sqrt(r)) = ac + bd r + (ad + bc) sqrt(r)
+				let (a, b) = self.parts(&r);
+				let (c, d) = n.parts(&r);
+				let rational = &(&a * &c) + &(&(&b * &d) * &r.radicand);
+				let root = &(&a * &d) + &(&b * &c);
+				Number::make(rational, root, &r)
+			}
+		}
+	}
+}
+
+impl Div for &Number {
+	type Output = Number;
+	// dividing is multiplying by the inverse
+	#[allow(clippy::suspicious_arithmetic_impl)]
+	fn div (self, n: &Number) -> Number { self * &n.inverse() }
+}
+
+// the same, taking ownership
+impl Add for Number {
+	type Output = Number;
+	fn add (self, n: Number) -> Number { &self + &n }
+}
+
+impl Sub for Number {
+	type Output = Number;
+	fn sub (self, n: Number) -> Number { &self - &n }
+}
+
+impl Mul for Number {
+	type Output = Number;
+	fn mul (self, n: Number) -> Number { &self * &n }
+}
+
+impl Div for Number {
+	type Output = Number;
+	fn div (self, n: Number) -> Number { &self / &n }
+}
+
+impl Neg for Number {
+	type Output = Number;
+	fn neg (self) -> Number { -&self }
+}
+
+impl PartialEq for Number {
+	fn eq (&self, n: &Number) -> bool { (self - n).is_zero() }
+}
+
+impl PartialOrd for Number {
+	fn partial_cmp (&self, n: &Number) -> Option<Ordering> { Some((self - n).signum().cmp(&0)) }
+}
+
+impl From<Rational> for Number {
+	fn from (q: Rational) -> Number { Number(Value::Rational(q)) }
+}
+
+impl fmt::Display for Number {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.0 {
+			Value::Rational(ref q) => write!(f, "{}", q),
+			Value::Extension(ref a, ref b, ref r) => write!(f, "({} + {} sqrt({}))", a, b, r.radicand)
+		}
+	}
+}
+
+impl fmt::Debug for Number {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self) }
+}
@@ -2,43 +2,50 @@ use std::fmt;
 // use std::iter::FromIterator;
 use super::vector::Vector;
 use super::segment::Segment;
-use super::common::EPSILON;
+use super::scalar::Scalar;
+use super::tolerance::Tolerance;
 
 #[derive(Copy, Clone)]
-pub struct Line {
-	pub u: Vector,
-	pub d: f64
+pub struct Line<T = f64> {
+	pub u: Vector<T>,
+	pub d: T
 }
 
-impl Line {
+impl<T: Scalar> Line<T> {
 	// @returns a tuple: true/false if intersection is possible and the point.
-	pub fn intersect (&self, l: Line) -> (bool, Vector) {
-		let det = self.u.determinant(l.u);
-		if det.abs() < EPSILON {
-			return (false, Vector { x: 0.0, y: 0.0 });
+	pub fn intersect (&self, l: Line<T>) -> (bool, Vector<T>) {
+		self.intersect_with(l, &Tolerance::default())
+	}
+	pub fn intersect_with (&self, l: Line<T>, tolerance: &Tolerance<T>) -> (bool, Vector<T>) {
+		let det = self.u.determinant(l.u.clone());
+		if self.u.parallel_with(l.u.clone(), tolerance) {
+			return (false, Vector { x: T::zero(), y: T::zero() });
 		}
-		let x = self.d * l.u.y - l.d * self.u.y;
-		let y = l.d * self.u.x - self.d * l.u.x;
-		return (true, Vector { x: x / det, y: y / det });
+		let x = self.d.clone() * l.u.y.clone() - l.d.clone() * self.u.y.clone();
+		let y = l.d * self.u.x.clone() - self.d.clone() * l.u.x;
+		return (true, Vector { x: x / det.clone(), y: y / det });
+	}
+	pub fn equivalent (&self, l: Line<T>) -> bool {
+		self.equivalent_with(l, &Tolerance::default())
 	}
-	pub fn equivalent (&self, l: Line) -> bool {
+	pub fn equivalent_with (&self, l: Line<T>, tolerance: &Tolerance<T>) -> bool {
 		// check if lines are parallel
-		(self.u.dot(l.u.rotate90()).abs() < EPSILON) &&
+		self.u.parallel_with(l.u.clone(), tolerance) &&
 		// instead of simply comparing the .d values,
 		// scale the incoming by the dot prod of both .u normals
 		// this allows (1,0) and (-1,0) to be treated the same
-		((self.d - l.d * self.u.dot(l.u)).abs() < EPSILON)
+		tolerance.zero(self.d.clone() - l.d * self.u.dot(l.u))
 	}
 	// use this line as a mirror plane, reflect the point to the other side
-	pub fn reflect_vector (&self, p: Vector) -> Vector {
-		let v1 = self.u.scale(self.d);
+	pub fn reflect_vector (&self, p: Vector<T>) -> Vector<T> {
+		let v1 = self.u.scale(self.d.clone());
 		let rot90 = self.u.rotate90();
-		let v2 = rot90.scale(p.dot(rot90));
+		let v2 = rot90.scale(p.dot(rot90.clone()));
 		let projection = v1.add(v2);
 		return projection.add(projection.subtract(p));
 	}
 	// use this line as a mirror plane, reflect a segment to the other side
-	pub fn reflect_segment (&self, s: Segment) -> Segment {
+	pub fn reflect_segment (&self, s: Segment<T>) -> Segment<T> {
 		Segment {
 			a: self.reflect_vector(s.a),
 			b: self.reflect_vector(s.b)
@@ -46,7 +53,7 @@ impl Line {
 	}
 }
 
-impl fmt::Debug for Line {
+impl<T: fmt::Debug> fmt::Debug for Line<T> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("Line")
 			.field("x", &self.u.x)
@@ -0,0 +1,61 @@
+use std::fmt;
+use super::vector::Vector;
+use super::line::Line;
+use super::segment::Segment;
+use super::common::EPSILON;
+
+// an affine transform, in the same order as the SVG matrix(a b c d e f):
+// x' = a x + c y + e, y' = b x + d y + f
+#[derive(Copy, Clone, PartialEq)]
+pub struct Matrix(pub [f64; 6]);
+
+impl Matrix {
+	pub const IDENTITY: Matrix = Matrix([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+	// the mirror across a line, found by reflecting the origin and the two axes
+	pub fn reflection (line: Line) -> Matrix {
+		let origin = line.reflect_vector(Vector { x: 0.0, y: 0.0 });
+		let x = line.reflect_vector(Vector { x: 1.0, y: 0.0 }).subtract(origin);
+		let y = line.reflect_vector(Vector { x: 0.0, y: 1.0 }).subtract(origin);
+		Matrix([x.x, x.y, y.x, y.y, origin.x, origin.y])
+	}
+	// this transform after the other one
+	pub fn multiply (&self, n: Matrix) -> Matrix {
+		let (m, n) = (self.0, n.0);
+		Matrix([
+			m[0] * n[0] + m[2] * n[1],
+			m[1] * n[0] + m[3] * n[1],
+			m[0] * n[2] + m[2] * n[3],
+			m[1] * n[2] + m[3] * n[3],
+			m[0] * n[4] + m[2] * n[5] + m[4],
+			m[1] * n[4] + m[3] * n[5] + m[5]
+		])
+	}
+	pub fn determinant (&self) -> f64 {
+		self.0[0] * self.0[3] - self.0[1] * self.0[2]
+	}
+	pub fn inverse (&self) -> Option<Matrix> {
+		let m = self.0;
+		let det = self.determinant();
+		if det.abs() < EPSILON { return None; }
+		let (a, b, c, d) = (m[3] / det, -m[1] / det, -m[2] / det, m[0] / det);
+		return Some(Matrix([a, b, c, d, -(a * m[4] + c * m[5]), -(b * m[4] + d * m[5])]));
+	}
+	// does the transform turn the paper over
+	pub fn flips (&self) -> bool { self.determinant() < 0.0 }
+	pub fn transform (&self, p: Vector) -> Vector {
+		let m = self.0;
+		Vector { x: m[0] * p.x + m[2] * p.y + m[4], y: m[1] * p.x + m[3] * p.y + m[5] }
+	}
+	pub fn transform_segment (&self, s: Segment) -> Segment {
+		Segment { a: self.transform(s.a), b: self.transform(s.b) }
+	}
+	pub fn equivalent (&self, n: Matrix) -> bool {
+		self.0.iter().zip(n.0.iter()).all(|(a, b)| (a - b).abs() < EPSILON)
+	}
+}
+
+impl fmt::Debug for Matrix {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Matrix({} {} {} {} {} {})", self.0[0], self.0[1], self.0[2], self.0[3], self.0[4], self.0[5])
+	}
+}
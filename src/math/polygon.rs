@@ -0,0 +1,374 @@
+use std::fmt;
+use std::f64::consts::PI;
+use super::vector::Vector;
+use super::line::Line;
+use super::segment::Segment;
+use super::rect::Rect;
+use super::boundary::Boundary;
+use super::boundary::convex_contains;
+use super::boundary::convex_clip;
+use super::boundary::NULL_SEGMENT;
+use super::common::EPSILON;
+use super::tolerance::Tolerance;
+use super::predicates;
+
+// a convex polygon described by its sides, each side is a Line with
+// a normal that points outwards. the sides are stored counter-clockwise,
+// and vertex i is where side i meets side i + 1.
+#[derive(Clone)]
+pub struct ConvexPolygon {
+	sides: Vec<Line>,
+	vertices: Vec<Vector>
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PolygonError {
+	// fewer than 3 sides (or points)
+	TooFewSides,
+	// the side at this index does not have a unit normal
+	NotNormalized(usize),
+	// the side at this index is parallel to the side that follows it
+	ParallelSides(usize),
+	// the side at this index turns the wrong way, or is outside the others
+	NotConvex(usize)
+}
+
+impl fmt::Display for PolygonError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			PolygonError::TooFewSides =>
+				write!(f, "a polygon needs at least 3 sides"),
+			PolygonError::NotNormalized(i) =>
+				write!(f, "side {} does not have a unit normal", i),
+			PolygonError::ParallelSides(i) =>
+				write!(f, "side {} is parallel to the next side", i),
+			PolygonError::NotConvex(i) =>
+				write!(f, "side {} makes the polygon non-convex", i)
+		}
+	}
+}
+
+impl ::std::error::Error for PolygonError {}
+
+impl ConvexPolygon {
+	// the sides need to be in counter-clockwise order with outward normals
+	pub fn new (sides: Vec<Line>) -> Result<ConvexPolygon, PolygonError> {
+		let n = sides.len();
+		if n < 3 { return Err(PolygonError::TooFewSides); }
+		if let Some(i) = sides.iter()
+			.position(|s| (s.u.magnitude() - 1.0).abs() > EPSILON) {
+			return Err(PolygonError::NotNormalized(i));
+		}
+		let mut vertices: Vec<Vector> = vec![];
+		let mut turning = 0.0;
+		for i in 0..n {
+			let (a, b) = (sides[i], sides[(i + 1) % n]);
+			let (success, point) = a.intersect(b);
+			if !success { return Err(PolygonError::ParallelSides(i)); }
+			// normals must rotate counter-clockwise from one side to the next
+			let det = a.u.determinant(b.u);
+			if det < EPSILON { return Err(PolygonError::NotConvex(i)); }
+			turning += det.atan2(a.u.dot(b.u));
+			vertices.push(point);
+		}
+		// every corner must be inside every side. this catches sides that
+		// are redundant (they miss the polygon entirely) or in the wrong order
+		if let Some(i) = vertices.iter()
+			.position(|v| !convex_contains(&sides, *v, &Tolerance::default())) {
+			return Err(PolygonError::NotConvex(i));
+		}
+		// the normals must wind around exactly once
+		if (turning - 2.0 * PI).abs() > EPSILON {
+			return Err(PolygonError::NotConvex(0));
+		}
+		return Ok(ConvexPolygon { sides, vertices });
+	}
+	// build the polygon from its corners, in either winding direction
+	pub fn from_points (points: &[Vector]) -> Result<ConvexPolygon, PolygonError> {
+		if points.len() < 3 { return Err(PolygonError::TooFewSides); }
+		let area: f64 = points.iter().enumerate()
+			.map(|(i, p)| p.determinant(points[(i + 1) % points.len()]))
+			.sum();
+		let mut ordered: Vec<Vector> = points.to_vec();
+		if area < 0.0 { ordered.reverse(); }
+		let n = ordered.len();
+		// side i goes from point i to point i + 1, outward is to the right
+		let sides: Vec<Line> = (0..n)
+			.map(|i| (ordered[i], ordered[(i + 1) % n]))
+			.map(|(a, b)| (a, b.subtract(a).rotate270().normalize()))
+			.map(|(a, u)| Line { u, d: a.dot(u) })
+			.collect();
+		return ConvexPolygon::new(sides);
+	}
+	pub fn sides (&self) -> &[Line] { &self.sides }
+	pub fn vertices (&self) -> &[Vector] { &self.vertices }
+	pub fn contains (&self, p: Vector) -> bool {
+		self.contains_with(p, &Tolerance::default())
+	}
+	pub fn contains_with (&self, p: Vector, tolerance: &Tolerance) -> bool {
+		convex_contains(&self.sides, p, tolerance)
+	}
+	// @returns a tuple: true/false if clip is possible and the segment.
+	pub fn clip (&self, l: Line) -> (bool, Segment) {
+		self.clip_with(l, &Tolerance::default())
+	}
+	pub fn clip_with (&self, l: Line, tolerance: &Tolerance) -> (bool, Segment) {
+		convex_clip(&self.vertices, l, tolerance)
+	}
+}
+
+impl From<Rect> for ConvexPolygon {
+	fn from (rect: Rect) -> ConvexPolygon {
+		ConvexPolygon { sides: rect.sides.to_vec(), vertices: rect.vertices() }
+	}
+}
+
+impl Boundary for ConvexPolygon {
+	fn contains (&self, p: Vector) -> bool { ConvexPolygon::contains(self, p) }
+	fn contains_with (&self, p: Vector, tolerance: &Tolerance) -> bool {
+		ConvexPolygon::contains_with(self, p, tolerance)
+	}
+	fn clip (&self, l: Line) -> (bool, Segment) { ConvexPolygon::clip(self, l) }
+	fn clip_with (&self, l: Line, tolerance: &Tolerance) -> (bool, Segment) {
+		ConvexPolygon::clip_with(self, l, tolerance)
+	}
+	fn rings (&self) -> Vec<Vec<Vector>> { vec![self.vertices.clone()] }
+}
+
+impl fmt::Debug for ConvexPolygon {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ConvexPolygon")
+			.field("vertices", &self.vertices)
+			.finish()
+	}
+}
+
+// a simple polygon which may be non-convex and may contain holes,
+// like an L-shaped sheet, or a sheet with a cut-out window.
+// the outer ring is stored counter-clockwise, the holes clockwise.
+#[derive(Clone)]
+pub struct Polygon {
+	outer: Vec<Vector>,
+	holes: Vec<Vec<Vector>>
+}
+
+pub(crate) fn signed_area (ring: &[Vector]) -> f64 {
+	ring.iter().enumerate()
+		.map(|(i, p)| p.determinant(ring[(i + 1) % ring.len()]))
+		.sum::<f64>() / 2.0
+}
+
+// wind the ring so its signed area has the requested sign
+fn orient (ring: &[Vector], counter_clockwise: bool) -> Vec<Vector> {
+	let mut ordered = ring.to_vec();
+	if (signed_area(ring) > 0.0) != counter_clockwise { ordered.reverse(); }
+	return ordered;
+}
+
+// the edges of a ring as (start, end) pairs, closing the loop
+fn ring_edges (ring: &[Vector]) -> impl Iterator<Item = (Vector, Vector)> + '_ {
+	ring.iter().enumerate()
+		.map(move |(i, p)| (*p, ring[(i + 1) % ring.len()]))
+}
+
+pub(crate) fn on_edge (a: Vector, b: Vector, p: Vector) -> bool {
+	on_edge_within(a, b, p, EPSILON)
+}
+
+// the distance is a length, t is a fraction of the edge, so the ends are
+// allowed to be passed by the distance over the edge's length
+fn on_edge_within (a: Vector, b: Vector, p: Vector, distance: f64) -> bool {
+	let ab = b.subtract(a);
+	let len_sq = ab.magnitude_squared();
+	if len_sq < distance * distance { return a.distance_to(p) < distance; }
+	let t = p.subtract(a).dot(ab) / len_sq;
+	let past = distance / len_sq.sqrt();
+	return t >= -past && t <= 1.0 + past
+		&& a.add(ab.scale(t)).distance_to(p) < distance;
+}
+
+// winding number of a ring around a point. which side of each edge the
+// point is on comes from the robust predicate
+pub(crate) fn winding (ring: &[Vector], p: Vector) -> i32 {
+	ring_edges(ring).fold(0, |w, (a, b)| {
+		let side = predicates::orient2d(a, b, p);
+		if a.y <= p.y {
+			if b.y > p.y && side > 0.0 { w + 1 } else { w }
+		} else if b.y <= p.y && side < 0.0 { w - 1 } else { w }
+	})
+}
+
+// cut a simple ring (either winding) into triangles by clipping ears.
+// the triangles are counter-clockwise, vertices in a straight line are dropped.
+pub(crate) fn triangulate (ring: &[Vector]) -> Vec<[Vector; 3]> {
+	let mut ring = orient(ring, true);
+	let mut triangles = vec![];
+	while ring.len() >= 3 {
+		let n = ring.len();
+		let corner = |i: usize| (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+		let turn = |i: usize| {
+			let (a, b, c) = corner(i);
+			b.subtract(a).determinant(c.subtract(b))
+		};
+		// a corner in a straight line adds nothing, remove it first
+		if let Some(i) = (0..n).find(|i| turn(*i).abs() < EPSILON) {
+			ring.remove(i);
+			continue;
+		}
+		// an ear is a convex corner with no other vertex inside its triangle
+		let ear = (0..n).find(|i| {
+			let (a, b, c) = corner(*i);
+			let triangle = [a, b, c];
+			turn(*i) > 0.0 && ring.iter()
+				.filter(|p| !p.equivalent(a) && !p.equivalent(b) && !p.equivalent(c))
+				.all(|p| winding(&triangle, *p) == 0 && !ring_edges(&triangle).any(|(u, v)| on_edge(u, v, *p)))
+		});
+		match ear {
+			Some(i) => {
+				let (a, b, c) = corner(i);
+				triangles.push([a, b, c]);
+				ring.remove(i);
+			},
+			None => break
+		}
+	}
+	return triangles;
+}
+
+// the overlap of two convex counter-clockwise polygons (Sutherland-Hodgman),
+// empty if they don't overlap
+pub(crate) fn convex_intersection (subject: &[Vector], clip: &[Vector]) -> Vec<Vector> {
+	let mut output = subject.to_vec();
+	for (a, b) in ring_edges(clip) {
+		if output.is_empty() { break; }
+		let input = output;
+		output = vec![];
+		let side = |p: Vector| b.subtract(a).determinant(p.subtract(a));
+		for (p, q) in ring_edges(&input) {
+			let (side_p, side_q) = (side(p), side(q));
+			if side_p >= 0.0 { output.push(p); }
+			if (side_p >= 0.0) != (side_q >= 0.0) {
+				output.push(p.add(q.subtract(p).scale(side_p / (side_p - side_q))));
+			}
+		}
+	}
+	return output;
+}
+
+impl Polygon {
+	// rings can be in either winding direction
+	pub fn new (outer: &[Vector], holes: &[Vec<Vector>]) -> Result<Polygon, PolygonError> {
+		if outer.len() < 3 || holes.iter().any(|h| h.len() < 3) {
+			return Err(PolygonError::TooFewSides);
+		}
+		return Ok(Polygon {
+			outer: orient(outer, true),
+			holes: holes.iter().map(|h| orient(h, false)).collect()
+		});
+	}
+	pub fn outer (&self) -> &[Vector] { &self.outer }
+	pub fn holes (&self) -> &[Vec<Vector>] { &self.holes }
+	fn ring_iter (&self) -> impl Iterator<Item = &Vec<Vector>> {
+		Some(&self.outer).into_iter().chain(self.holes.iter())
+	}
+	// points on the boundary (including the edge of a hole) count as inside
+	pub fn contains (&self, p: Vector) -> bool {
+		self.contains_with(p, &Tolerance::default())
+	}
+	pub fn contains_with (&self, p: Vector, tolerance: &Tolerance) -> bool {
+		let on_ring = |ring: &[Vector]| ring_edges(ring)
+			.any(|(a, b)| on_edge_within(a, b, p, tolerance.distance));
+		if self.ring_iter().any(|ring| on_ring(ring)) {
+			return true;
+		}
+		return self.ring_iter().map(|ring| winding(ring, p)).sum::<i32>() != 0;
+	}
+	// every piece of the line that lies on the paper, sorted along the line
+	pub fn clip (&self, l: Line) -> Vec<Segment> {
+		self.clip_with(l, &Tolerance::default())
+	}
+	// crossings closer together than the tolerance distance are merged
+	pub fn clip_with (&self, l: Line, tolerance: &Tolerance) -> Vec<Segment> {
+		let origin = l.u.scale(l.d);
+		let vector = l.u.rotate90();
+		// the parameter along the line of every crossing with every edge
+		let mut ts: Vec<f64> = vec![];
+		for ring in self.ring_iter() {
+			for (a, b) in ring_edges(ring) {
+				// the side of the line each end is on, from the robust predicate
+				let side_a = predicates::side(l, a);
+				let side_b = predicates::side(l, b);
+				if side_a == 0.0 { ts.push(a.subtract(origin).dot(vector)); }
+				if side_b == 0.0 { ts.push(b.subtract(origin).dot(vector)); }
+				if (side_a < 0.0 && side_b > 0.0) || (side_a > 0.0 && side_b < 0.0) {
+					let (da, db) = (a.dot(l.u) - l.d, b.dot(l.u) - l.d);
+					let point = a.add(b.subtract(a).scale(da / (da - db)));
+					ts.push(point.subtract(origin).dot(vector));
+				}
+			}
+		}
+		ts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		ts.dedup_by(|a, b| tolerance.zero(*a - *b));
+		// keep the spans between crossings whose middle is on the paper,
+		// joining neighbors which only touch the boundary at a vertex
+		let mut pieces: Vec<(f64, f64)> = vec![];
+		for pair in ts.windows(2) {
+			let middle = origin.add(vector.scale((pair[0] + pair[1]) / 2.0));
+			if !self.contains_with(middle, tolerance) { continue; }
+			match pieces.last_mut() {
+				Some(last) if tolerance.zero(last.1 - pair[0]) => last.1 = pair[1],
+				_ => pieces.push((pair[0], pair[1]))
+			}
+		}
+		return pieces.iter()
+			.map(|(min, max)| Segment {
+				a: origin.add(vector.scale(*min)),
+				b: origin.add(vector.scale(*max))
+			})
+			.collect();
+	}
+}
+
+impl From<ConvexPolygon> for Polygon {
+	fn from (polygon: ConvexPolygon) -> Polygon {
+		Polygon { outer: polygon.vertices, holes: vec![] }
+	}
+}
+
+impl Boundary for Polygon {
+	fn contains (&self, p: Vector) -> bool { Polygon::contains(self, p) }
+	fn contains_with (&self, p: Vector, tolerance: &Tolerance) -> bool {
+		Polygon::contains_with(self, p, tolerance)
+	}
+	// the longest piece of the line on the paper
+	fn clip (&self, l: Line) -> (bool, Segment) { longest(Polygon::clip(self, l)) }
+	fn clip_with (&self, l: Line, tolerance: &Tolerance) -> (bool, Segment) {
+		longest(Polygon::clip_with(self, l, tolerance))
+	}
+	fn clip_all (&self, l: Line) -> Vec<Segment> { Polygon::clip(self, l) }
+	fn clip_all_with (&self, l: Line, tolerance: &Tolerance) -> Vec<Segment> {
+		Polygon::clip_with(self, l, tolerance)
+	}
+	fn rings (&self) -> Vec<Vec<Vector>> { self.ring_iter().cloned().collect() }
+}
+
+fn longest (pieces: Vec<Segment>) -> (bool, Segment) {
+	let longest = pieces.into_iter()
+		.fold(None, |longest: Option<Segment>, s| match longest {
+			Some(m) if m.a.distance_to(m.b) >= s.a.distance_to(s.b) => Some(m),
+			_ => Some(s)
+		});
+	match longest {
+		Some(segment) => (true, segment),
+		None => (false, NULL_SEGMENT)
+	}
+}
+
+impl fmt::Debug for Polygon {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Polygon")
+			.field("outer", &self.outer)
+			.field("holes", &self.holes)
+			.finish()
+	}
+}
@@ -0,0 +1,172 @@
+// real roots of polynomials, up to a quartic. coefficients are listed from
+// the highest degree down, [a, b, c] is a x^2 + b x + c. leading
+// coefficients which are (nearly) zero are dropped, so a cubic with a tiny
+// first coefficient is solved as a quadratic.
+//
+// quadratics are solved in closed form, higher degrees by finding the
+// critical points first (the roots of the derivative). between two critical
+// points the polynomial only goes up or only goes down, so it has at most one
+// root there, found with Newton's method kept inside the interval by
+// bisection. a critical point where the polynomial is zero is a multiple root.
+
+// how close is close enough. zero is relative to the size of the terms
+// being added, so it doesn't depend on the scale of the coefficients.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Settings {
+	// a value this small, relative to its terms, is zero
+	pub zero: f64,
+	// roots closer together than this are the same root
+	pub merge: f64,
+	// the most steps when searching for or polishing a root
+	pub iterations: usize
+}
+
+impl Default for Settings {
+	fn default () -> Settings {
+		Settings { zero: 1.0e-10, merge: 1.0e-9, iterations: 100 }
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Root {
+	pub value: f64,
+	// 2 for a double root, where the polynomial touches zero without crossing
+	pub multiplicity: usize
+}
+
+// the value of the polynomial at x (Horner's method)
+pub fn evaluate (coefficients: &[f64], x: f64) -> f64 {
+	coefficients.iter().fold(0.0, |sum, c| sum * x + c)
+}
+
+// the sum of the sizes of the terms at x, what a value is compared to
+// when deciding if it's zero
+fn magnitude (coefficients: &[f64], x: f64) -> f64 {
+	coefficients.iter().fold(0.0, |sum, c| sum * x.abs() + c.abs())
+}
+
+pub fn derivative (coefficients: &[f64]) -> Vec<f64> {
+	let degree = coefficients.len().saturating_sub(1);
+	coefficients.iter().take(degree).enumerate()
+		.map(|(i, c)| c * (degree - i) as f64)
+		.collect()
+}
+
+// the coefficients without the leading ones that are zero
+fn trim (coefficients: &[f64], settings: &Settings) -> Vec<f64> {
+	let largest = coefficients.iter().fold(0.0, |m: f64, c| m.max(c.abs()));
+	if largest == 0.0 { return vec![]; }
+	return coefficients.iter()
+		.skip_while(|c| c.abs() <= settings.zero * largest)
+		.cloned()
+		.collect();
+}
+
+// newton's method from x, stopping when the value stops getting smaller
+pub fn polish (coefficients: &[f64], x: f64, iterations: usize) -> f64 {
+	let slope = derivative(coefficients);
+	let mut x = x;
+	let mut value = evaluate(coefficients, x).abs();
+	for _ in 0..iterations {
+		if value == 0.0 { break; }
+		let d = evaluate(&slope, x);
+		if d == 0.0 { break; }
+		let next = x - evaluate(coefficients, x) / d;
+		let next_value = evaluate(coefficients, next).abs();
+		if next_value.is_nan() || next_value >= value { break; }
+		x = next;
+		value = next_value;
+	}
+	return x;
+}
+
+// the one root between a and b, where the signs of the values are different
+fn bracketed (coefficients: &[f64], a: f64, b: f64, settings: &Settings) -> f64 {
+	let slope = derivative(coefficients);
+	// lo is the end where the polynomial is negative
+	let (mut lo, mut hi) = if evaluate(coefficients, a) < 0.0 { (a, b) } else { (b, a) };
+	let mut x = (a + b) / 2.0;
+	for _ in 0..settings.iterations {
+		let value = evaluate(coefficients, x);
+		if value == 0.0 { return x; }
+		if value < 0.0 { lo = x; } else { hi = x; }
+		let d = evaluate(&slope, x);
+		let newton = if d == 0.0 { f64::NAN } else { x - value / d };
+		// bisect when the newton step leaves the interval
+		let inside = (newton - lo) * (newton - hi) < 0.0;
+		let next = if inside { newton } else { (lo + hi) / 2.0 };
+		if (next - x).abs() <= f64::EPSILON * x.abs().max(1.0) { return next; }
+		x = next;
+	}
+	return x;
+}
+
+pub fn linear (a: f64, b: f64) -> Vec<Root> { solve(&[a, b]) }
+pub fn quadratic (a: f64, b: f64, c: f64) -> Vec<Root> { solve(&[a, b, c]) }
+pub fn cubic (a: f64, b: f64, c: f64, d: f64) -> Vec<Root> { solve(&[a, b, c, d]) }
+pub fn quartic (a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<Root> { solve(&[a, b, c, d, e]) }
+
+// the real roots, smallest first
+pub fn solve (coefficients: &[f64]) -> Vec<Root> {
+	solve_with(coefficients, &Settings::default())
+}
+
+pub fn solve_with (coefficients: &[f64], settings: &Settings) -> Vec<Root> {
+	let p = trim(coefficients, settings);
+	let mut roots = match p.len() {
+		0 | 1 => vec![],
+		2 => vec![Root { value: -p[1] / p[0], multiplicity: 1 }],
+		3 => solve_quadratic(&p, settings),
+		_ => solve_critical(&p, settings)
+	};
+	roots.sort_by(|a, b| a.value.partial_cmp(&b.value).unwrap());
+	// roots which are really the same root
+	let mut merged: Vec<Root> = vec![];
+	for root in roots {
+		match merged.last_mut() {
+			Some(last) if root.value - last.value <= settings.merge => {
+				last.multiplicity += root.multiplicity;
+			},
+			_ => merged.push(root)
+		}
+	}
+	return merged;
+}
+
+// the form which doesn't lose precision when b^2 is much larger than 4ac
+fn solve_quadratic (p: &[f64], settings: &Settings) -> Vec<Root> {
+	let (a, b, c) = (p[0], p[1], p[2]);
+	let discriminant = b * b - 4.0 * a * c;
+	let scale = b * b + (4.0 * a * c).abs();
+	if discriminant.abs() <= settings.zero * scale {
+		return vec![Root { value: -b / (2.0 * a), multiplicity: 2 }];
+	}
+	if discriminant < 0.0 { return vec![]; }
+	let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+	return vec![q / a, c / q].into_iter()
+		.map(|x| Root { value: polish(p, x, settings.iterations), multiplicity: 1 })
+		.collect();
+}
+
+fn solve_critical (p: &[f64], settings: &Settings) -> Vec<Root> {
+	let critical = solve_with(&derivative(p), settings);
+	// every root is inside this (Cauchy's bound)
+	let bound = 1.0 + p.iter().skip(1).fold(0.0, |m: f64, c| m.max((c / p[0]).abs()));
+	let is_zero = |x: f64| evaluate(p, x).abs() <= settings.zero * magnitude(p, x);
+	let mut roots: Vec<Root> = critical.iter()
+		.filter(|c| is_zero(c.value))
+		.map(|c| Root { value: c.value, multiplicity: c.multiplicity + 1 })
+		.collect();
+	let mut ends = vec![-bound];
+	ends.extend(critical.iter().map(|c| c.value));
+	ends.push(bound);
+	for pair in ends.windows(2) {
+		let (a, b) = (pair[0], pair[1]);
+		// the polynomial only goes one way between the ends, if it's zero
+		// at one end, it isn't zero anywhere else
+		if is_zero(a) || is_zero(b) { continue; }
+		if evaluate(p, a).signum() == evaluate(p, b).signum() { continue; }
+		roots.push(Root { value: bracketed(p, a, b, settings), multiplicity: 1 });
+	}
+	return roots;
+}
@@ -0,0 +1,85 @@
+// geometric predicates that always get the sign right, after Jonathan
+// Shewchuk's "Adaptive Precision Floating-Point Arithmetic and Fast Robust
+// Geometric Predicates". each is computed with floats first, with a bound on
+// the rounding error. only when the answer is closer to zero than that bound
+// is it computed again, exactly, as a sum of floats that don't overlap
+// (an expansion), whose sign is the sign of its largest part.
+//
+// the answers are only signs (and approximate values). two tests built on the
+// same predicate can't disagree, which the epsilon comparisons could.
+use super::vector::Vector;
+use super::line::Line;
+
+// half of the distance from 1.0 to the next float
+const UNIT: f64 = f64::EPSILON / 2.0;
+// the error bounds of the float stage
+const ORIENT_BOUND: f64 = (3.0 + 16.0 * UNIT) * UNIT;
+const SIDE_BOUND: f64 = (4.0 + 16.0 * UNIT) * UNIT;
+
+// a + b exactly, as the rounded sum and the rounding error
+fn two_sum (a: f64, b: f64) -> (f64, f64) {
+	let x = a + b;
+	let virtual_b = x - a;
+	let virtual_a = x - virtual_b;
+	return (x, (a - virtual_a) + (b - virtual_b));
+}
+
+// a * b exactly, as the rounded product and the rounding error
+fn two_product (a: f64, b: f64) -> (f64, f64) {
+	let x = a * b;
+	return (x, a.mul_add(b, -x));
+}
+
+// add a float to an expansion (smallest part first), keeping it exact
+fn grow (expansion: &[f64], b: f64) -> Vec<f64> {
+	let mut q = b;
+	let mut result: Vec<f64> = Vec::with_capacity(expansion.len() + 1);
+	for e in expansion {
+		let (sum, error) = two_sum(q, *e);
+		if error != 0.0 { result.push(error); }
+		q = sum;
+	}
+	if q != 0.0 || result.is_empty() { result.push(q); }
+	return result;
+}
+
+// the exact sum of the terms, rounded to the nearest float
+// (with the sign of the exact sum)
+fn exact_sum (terms: &[f64]) -> f64 {
+	let expansion = terms.iter().fold(vec![], |e, t| grow(&e, *t));
+	return expansion.iter().rev().cloned().find(|e| *e != 0.0).unwrap_or(0.0);
+}
+
+// positive if a, b, c turn counter-clockwise, negative if clockwise,
+// zero if they are collinear. the size is twice the triangle's area.
+pub fn orient2d (a: Vector, b: Vector, c: Vector) -> f64 {
+	let left = (a.x - c.x) * (b.y - c.y);
+	let right = (a.y - c.y) * (b.x - c.x);
+	let det = left - right;
+	if det.abs() >= ORIENT_BOUND * (left.abs() + right.abs()) { return det; }
+	// ax by - ay bx + bx cy - by cx + cx ay - cy ax
+	let products = [
+		two_product(a.x, b.y), two_product(-a.y, b.x),
+		two_product(b.x, c.y), two_product(-b.y, c.x),
+		two_product(c.x, a.y), two_product(-c.y, a.x)
+	];
+	let terms: Vec<f64> = products.iter().flat_map(|(x, y)| vec![*y, *x]).collect();
+	return exact_sum(&terms);
+}
+
+// positive if the point is on the side of the line its normal points to,
+// negative on the other side, zero if it's on the line. the size is the
+// distance to the line, times the length of the normal.
+pub fn side (l: Line, p: Vector) -> f64 {
+	let x = p.x * l.u.x;
+	let y = p.y * l.u.y;
+	let value = x + y - l.d;
+	if value.abs() >= SIDE_BOUND * (x.abs() + y.abs() + l.d.abs()) { return value; }
+	let (x, x_error) = two_product(p.x, l.u.x);
+	let (y, y_error) = two_product(p.y, l.u.y);
+	return exact_sum(&[x_error, y_error, x, y, -l.d]);
+}
+
+pub fn collinear (a: Vector, b: Vector, c: Vector) -> bool {
+	orient2d(a, b, c) == 0.0
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+use std::cmp::Ordering;
+use super::vector::Vector;
+use super::line::Line;
+use super::segment::Segment;
+use super::segment::within;
+use super::segment::line_parameter;
+use super::scalar::Scalar;
+use super::tolerance::Tolerance;
+
+// a half line, from the origin on forever in the direction of the vector.
+// the t of a point is how many vectors it is from the origin.
+#[derive(Copy, Clone)]
+pub struct Ray<T = f64> {
+	pub origin: Vector<T>,
+	pub vector: Vector<T>
+}
+
+impl<T: Scalar> Ray<T> {
+	pub fn point_at (&self, t: T) -> Vector<T> { self.origin.add(self.vector.scale(t)) }
+	// t of the point on the (infinite) line through the ray nearest p
+	pub fn parameter (&self, p: Vector<T>) -> T {
+		let length_sq = self.vector.magnitude_squared();
+		if length_sq <= T::zero() { return T::zero(); }
+		return p.subtract(self.origin.clone()).dot(self.vector.clone()) / length_sq;
+	}
+	pub fn closest_point (&self, p: Vector<T>) -> Vector<T> {
+		let t = self.parameter(p);
+		return if t < T::zero() { self.origin.clone() } else { self.point_at(t) };
+	}
+	pub fn distance_to (&self, p: Vector<T>) -> T {
+		self.closest_point(p.clone()).distance_to(p)
+	}
+	// is the point on the ray (the origin included)
+	pub fn contains (&self, p: Vector<T>) -> bool {
+		self.contains_with(p, &Tolerance::default())
+	}
+	pub fn contains_with (&self, p: Vector<T>, tolerance: &Tolerance<T>) -> bool {
+		// without a direction the ray is only its origin
+		if self.vector.magnitude_squared() <= T::zero() {
+			return tolerance.zero(self.origin.distance_to(p));
+		}
+		let ahead = self.origin.add(self.vector.clone());
+		if T::orient(&self.origin, &ahead, &p) == Ordering::Equal
+		&& p.subtract(self.origin.clone()).dot(self.vector.clone()) >= T::zero() {
+			return true;
+		}
+		return tolerance.zero(self.distance_to(p));
+	}
+	// @returns the point, and the t along the ray
+	pub fn intersect_line (&self, l: Line<T>) -> Option<(Vector<T>, T)> {
+		self.intersect_line_with(l, &Tolerance::default())
+	}
+	pub fn intersect_line_with (&self, l: Line<T>, tolerance: &Tolerance<T>) -> Option<(Vector<T>, T)> {
+		let t = line_parameter(&self.origin, &self.vector, &l, tolerance)?;
+		if !ahead(&t, self.vector.magnitude(), tolerance) { return None; }
+		return Some((self.point_at(t.clone()), t));
+	}
+	// @returns the point, the t along the ray and the t along the segment.
+	// a ray parallel to the segment doesn't cross it at one point.
+	pub fn intersect_segment (&self, s: Segment<T>) -> Option<(Vector<T>, T, T)> {
+		self.intersect_segment_with(s, &Tolerance::default())
+	}
+	pub fn intersect_segment_with (&self, s: Segment<T>, tolerance: &Tolerance<T>) -> Option<(Vector<T>, T, T)> {
+		let (r, q) = (self.vector.clone(), s.vector());
+		if r.parallel_with(q.clone(), tolerance) { return None; }
+		let det = r.determinant(q.clone());
+		let ac = s.a.subtract(self.origin.clone());
+		let t = ac.determinant(q.clone()) / det.clone();
+		let u = ac.determinant(r.clone()) / det;
+		if !ahead(&t, r.magnitude(), tolerance) || !within(&u, q.magnitude(), tolerance) {
+			return None;
+		}
+		return Some((self.point_at(t.clone()), t, u));
+	}
+}
+
+// is t positive, or no more than the tolerance distance behind the origin
+fn ahead<T: Scalar> (t: &T, length: T, tolerance: &Tolerance<T>) -> bool {
+	-t.clone() * length <= tolerance.distance
+}
+
+impl<T: fmt::Debug> fmt::Debug for Ray<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Ray")
+			.field("x", &self.origin.x)
+			.field("y", &self.origin.y)
+			.field("vx", &self.vector.x)
+			.field("vy", &self.vector.y)
+			.finish()
+	}
+}
@@ -3,48 +3,47 @@
 use super::vector::Vector;
 use super::line::Line;
 use super::segment::Segment;
-use super::common::EPSILON;
+use super::boundary::Boundary;
+use super::boundary::convex_contains;
+use super::boundary::convex_clip;
+use super::scalar::Scalar;
+use super::tolerance::Tolerance;
 
 #[derive(Copy, Clone)]
-pub struct Rect {
-	pub sides: [Line; 4]
+pub struct Rect<T = f64> {
+	pub sides: [Line<T>; 4]
 }
 
-const NULL_SEGMENT: Segment = Segment {
-	a: Vector {x:0.0, y:0.0},
-	b: Vector {x:0.0, y:0.0}
-};
-
-impl Rect {
-	// todo: this is currently hard-coded to a unit square
-	pub fn contains (&self, p: Vector) -> bool {
-		p.x >= 0.0 && p.x <= 1.0 &&
-		p.y >= 0.0 && p.y <= 1.0
+impl<T: Scalar> Rect<T> {
+	// the sides must be Lines with normals that point outwards
+	pub fn contains (&self, p: Vector<T>) -> bool {
+		self.contains_with(p, &Tolerance::default())
+	}
+	pub fn contains_with (&self, p: Vector<T>, tolerance: &Tolerance<T>) -> bool {
+		convex_contains(&self.sides, p, tolerance)
 	}
 	// @returns a tuple: true/false if clip is possible and the segment.
-	pub fn clip (&self, l: Line) -> (bool, Segment) {
-		// test intersection with every side, exclude pts outside polygon
-		let results: Vec<Vector> = self.sides.iter()
-			.map(|line| line.intersect(l))
-			.map(|(success, seg)| (success, self.contains(seg), seg))
-			.filter(|el| el.0 && el.1)
-			.map(|el| el.2)
-			.collect();
-		if results.len() < 2 { return (false, NULL_SEGMENT); }
-		// sort intersection points along line
-		let origin = l.u.scale(l.d);
-		let vector = l.u.rotate90();
-		let ts: Vec<f64> = results.iter()
-			.map(|pt| pt.subtract(origin).dot(vector))
-			.collect();
-		// get the min and max, construct a segment between them
-		let min = *ts.iter().fold(&ts[0], |a, b| if b < a {b} else {a});
-		let max = *ts.iter().fold(&ts[0], |a, b| if b > a {b} else {a});
-		// if the two points are the same the segment is degenerate
-		if max - min < EPSILON { return (false, NULL_SEGMENT); }
-		return (true, Segment {
-			a: origin.add(vector.scale(min)),
-			b: origin.add(vector.scale(max))
-		});
+	pub fn clip (&self, l: Line<T>) -> (bool, Segment<T>) {
+		self.clip_with(l, &Tolerance::default())
+	}
+	pub fn clip_with (&self, l: Line<T>, tolerance: &Tolerance<T>) -> (bool, Segment<T>) {
+		convex_clip(&self.vertices(), l, tolerance)
+	}
+	// corner i is where side i meets side i + 1
+	pub fn vertices (&self) -> Vec<Vector<T>> {
+		(0..4).map(|i| self.sides[i].intersect(self.sides[(i + 1) % 4].clone()).1)
+			.collect()
+	}
+}
+
+impl<T: Scalar> Boundary<T> for Rect<T> {
+	fn contains (&self, p: Vector<T>) -> bool { Rect::contains(self, p) }
+	fn contains_with (&self, p: Vector<T>, tolerance: &Tolerance<T>) -> bool {
+		Rect::contains_with(self, p, tolerance)
+	}
+	fn clip (&self, l: Line<T>) -> (bool, Segment<T>) { Rect::clip(self, l) }
+	fn clip_with (&self, l: Line<T>, tolerance: &Tolerance<T>) -> (bool, Segment<T>) {
+		Rect::clip_with(self, l, tolerance)
 	}
+	fn rings (&self) -> Vec<Vec<Vector<T>>> { vec![self.vertices()] }
 }
@@ -0,0 +1,91 @@
+// the numbers that vectors, lines and the axioms can be made of. anything
+// with arithmetic, a square root and a tolerance will do: f64 (the default
+// everywhere), f32, exact numbers, or a type like an interval or a dual number.
+use std::fmt;
+use std::cmp::Ordering;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use super::common::EPSILON;
+use super::exact::Number;
+use super::vector::Vector;
+use super::line::Line;
+use super::predicates;
+
+pub trait Scalar: Clone + PartialOrd + fmt::Debug
+	+ Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+	+ Div<Output = Self> + Neg<Output = Self> {
+	fn zero () -> Self;
+	fn one () -> Self;
+	fn from_f64 (n: f64) -> Self;
+	fn to_f64 (&self) -> f64;
+	fn sqrt (&self) -> Self;
+	fn abs (&self) -> Self {
+		if *self < Self::zero() { -self.clone() } else { self.clone() }
+	}
+	// differences this small (or smaller) are zero. 0 for an exact type.
+	fn epsilon () -> Self;
+	// Greater if a, b, c turn counter-clockwise, Less if clockwise, Equal if
+	// they are collinear. this and side have to be exact, for floats they
+	// are the robust predicates, for an exact type the arithmetic will do.
+	fn orient (a: &Vector<Self>, b: &Vector<Self>, c: &Vector<Self>) -> Ordering {
+		let ab = b.subtract(a.clone());
+		let ac = c.subtract(a.clone());
+		return sign(ab.determinant(ac));
+	}
+	// Greater if the point is on the side the line's normal points to,
+	// Less on the other side, Equal on the line
+	fn side (l: &Line<Self>, p: &Vector<Self>) -> Ordering {
+		sign(p.dot(l.u.clone()) - l.d.clone())
+	}
+}
+
+fn sign<T: Scalar> (x: T) -> Ordering {
+	x.partial_cmp(&T::zero()).unwrap_or(Ordering::Equal)
+}
+
+impl Scalar for f64 {
+	fn zero () -> f64 { 0.0 }
+	fn one () -> f64 { 1.0 }
+	fn from_f64 (n: f64) -> f64 { n }
+	fn to_f64 (&self) -> f64 { *self }
+	fn sqrt (&self) -> f64 { f64::sqrt(*self) }
+	fn abs (&self) -> f64 { f64::abs(*self) }
+	fn epsilon () -> f64 { EPSILON }
+	fn orient (a: &Vector, b: &Vector, c: &Vector) -> Ordering {
+		sign(predicates::orient2d(*a, *b, *c))
+	}
+	fn side (l: &Line, p: &Vector) -> Ordering {
+		sign(predicates::side(*l, *p))
+	}
+}
+
+impl Scalar for f32 {
+	fn zero () -> f32 { 0.0 }
+	fn one () -> f32 { 1.0 }
+	fn from_f64 (n: f64) -> f32 { n as f32 }
+	fn to_f64 (&self) -> f64 { f64::from(*self) }
+	fn sqrt (&self) -> f32 { f32::sqrt(*self) }
+	fn abs (&self) -> f32 { f32::abs(*self) }
+	fn epsilon () -> f32 { 1.0e-5 }
+	// every f32 is an f64, so the f64 predicates are exact here too
+	fn orient (a: &Vector<f32>, b: &Vector<f32>, c: &Vector<f32>) -> Ordering {
+		f64::orient(&wide(a), &wide(b), &wide(c))
+	}
+	fn side (l: &Line<f32>, p: &Vector<f32>) -> Ordering {
+		f64::side(&Line { u: wide(&l.u), d: f64::from(l.d) }, &wide(p))
+	}
+}
+
+fn wide (v: &Vector<f32>) -> Vector {
+	Vector { x: f64::from(v.x), y: f64::from(v.y) }
+}
+
+// a float is converted exactly, and the square root of a negative number panics
+impl Scalar for Number {
+	fn zero () -> Number { Number::zero() }
+	fn one () -> Number { Number::one() }
+	fn from_f64 (n: f64) -> Number { Number::from_f64(n).expect("not a finite number") }
+	fn to_f64 (&self) -> f64 { Number::to_f64(self) }
+	fn sqrt (&self) -> Number { Number::sqrt(self).expect("square root of a negative number") }
+	fn abs (&self) -> Number { Number::abs(self) }
+	fn epsilon () -> Number { Number::zero() }
+}
@@ -1,39 +1,135 @@
 use std::fmt;
+use std::cmp::Ordering;
 // use std::iter::FromIterator;
 use super::vector::Vector;
+use super::line::Line;
+use super::scalar::Scalar;
+use super::tolerance::Tolerance;
 
 #[derive(Copy, Clone)]
-pub struct Segment {
-	pub a: Vector,
-	pub b: Vector
+pub struct Segment<T = f64> {
+	pub a: Vector<T>,
+	pub b: Vector<T>
 }
 
-impl Segment {
+impl<T: Scalar> Segment<T> {
+	// from a to b, the length of the segment
+	pub fn vector (&self) -> Vector<T> { self.b.subtract(self.a.clone()) }
+	pub fn length (&self) -> T { self.vector().magnitude() }
+	// the point at t along the segment, 0 is a and 1 is b
+	pub fn point_at (&self, t: T) -> Vector<T> { self.a.add(self.vector().scale(t)) }
+	// t of the point on the (infinite) line through the segment nearest p
+	pub fn parameter (&self, p: Vector<T>) -> T {
+		let vector = self.vector();
+		let length_sq = vector.magnitude_squared();
+		if length_sq <= T::zero() { return T::zero(); }
+		return p.subtract(self.a.clone()).dot(vector) / length_sq;
+	}
+	pub fn closest_point (&self, p: Vector<T>) -> Vector<T> {
+		self.point_at(clamp(self.parameter(p)))
+	}
+	pub fn distance_to (&self, p: Vector<T>) -> T {
+		self.closest_point(p.clone()).distance_to(p)
+	}
+	// is the point on the segment (ends included)
+	pub fn contains (&self, p: Vector<T>) -> bool {
+		self.contains_with(p, &Tolerance::default())
+	}
+	pub fn contains_with (&self, p: Vector<T>, tolerance: &Tolerance<T>) -> bool {
+		// a segment with no length is only its one point, every point is
+		// collinear with it so the orientation says nothing
+		if self.a == self.b { return tolerance.zero(self.a.distance_to(p)); }
+		if T::orient(&self.a, &self.b, &p) == Ordering::Equal && self.spans(&p) {
+			return true;
+		}
+		return tolerance.zero(self.distance_to(p));
+	}
+	// @returns the point, and the t along this segment and along the other.
+	// parallel segments don't cross at one point, even if they overlap.
+	pub fn intersect_segment (&self, s: Segment<T>) -> Option<(Vector<T>, T, T)> {
+		self.intersect_segment_with(s, &Tolerance::default())
+	}
+	pub fn intersect_segment_with (&self, s: Segment<T>, tolerance: &Tolerance<T>) -> Option<(Vector<T>, T, T)> {
+		let (r, q) = (self.vector(), s.vector());
+		if r.parallel_with(q.clone(), tolerance) { return None; }
+		let det = r.determinant(q.clone());
+		let ac = s.a.subtract(self.a.clone());
+		let t = ac.determinant(q.clone()) / det.clone();
+		let u = ac.determinant(r.clone()) / det;
+		if !within(&t, r.magnitude(), tolerance) || !within(&u, q.magnitude(), tolerance) {
+			return None;
+		}
+		return Some((self.point_at(t.clone()), t, u));
+	}
+	// @returns the point, and the t along this segment
+	pub fn intersect_line (&self, l: Line<T>) -> Option<(Vector<T>, T)> {
+		self.intersect_line_with(l, &Tolerance::default())
+	}
+	pub fn intersect_line_with (&self, l: Line<T>, tolerance: &Tolerance<T>) -> Option<(Vector<T>, T)> {
+		let vector = self.vector();
+		let t = line_parameter(&self.a, &vector, &l, tolerance)?;
+		if !within(&t, vector.magnitude(), tolerance) { return None; }
+		return Some((self.point_at(t.clone()), t));
+	}
+	// cut the segment at each t strictly between 0 and 1, in order. repeated
+	// cuts and cuts at the ends are ignored, the pieces cover the segment.
+	pub fn split (&self, ts: &[T]) -> Vec<Segment<T>> {
+		let mut cuts: Vec<T> = ts.iter()
+			.filter(|t| **t > T::zero() && **t < T::one())
+			.cloned()
+			.collect();
+		cuts.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+		cuts.dedup_by(|a, b| *a == *b);
+		let points: Vec<Vector<T>> = ::std::iter::once(self.a.clone())
+			.chain(cuts.into_iter().map(|t| self.point_at(t)))
+			.chain(::std::iter::once(self.b.clone()))
+			.collect();
+		return points.windows(2)
+			.map(|pair| Segment { a: pair[0].clone(), b: pair[1].clone() })
+			.collect();
+	}
 	// given we already know these two segments are collinear
-	// check if they also overlap
-	pub fn quick_overlap (&self, b: Segment) -> bool {
-		// seg a
-		let vec_a = self.b.subtract(self.a);
-		let mag_a = vec_a.magnitude();
-		let norm_a = vec_a.normalize();
-		// seg b
-		let vec_b = b.b.subtract(b.a);
-		let mag_b = vec_b.magnitude();
-		let norm_b = vec_b.normalize();
-		// project the other other segment's points onto its vector
-		let aa = norm_b.dot(self.a.subtract(b.a));
-		let ab = norm_b.dot(self.b.subtract(b.a));
-		let ba = norm_a.dot(b.a.subtract(self.a));
-		let bb = norm_a.dot(b.b.subtract(self.a));
-		let t1 = aa >= 0.0 && aa <= mag_b;
-		let t2 = ab >= 0.0 && ab <= mag_b;
-		let t3 = ba >= 0.0 && ba <= mag_a;
-		let t4 = bb >= 0.0 && bb <= mag_a;
-		return t1 || t2 || t3 || t4;
+	// check if they also overlap: if an end of either is between
+	// the ends of the other
+	pub fn quick_overlap (&self, b: Segment<T>) -> bool {
+		self.spans(&b.a) || self.spans(&b.b) || b.spans(&self.a) || b.spans(&self.b)
+	}
+	// is the (collinear) point between the ends. this compares coordinates
+	// along the axis the segment is longest in, so nothing is rounded
+	pub(crate) fn spans (&self, p: &Vector<T>) -> bool {
+		let dx = (self.b.x.clone() - self.a.x.clone()).abs();
+		let dy = (self.b.y.clone() - self.a.y.clone()).abs();
+		let (a, b, p) = if dx >= dy { (&self.a.x, &self.b.x, &p.x) }
+			else { (&self.a.y, &self.b.y, &p.y) };
+		return (a <= p && p <= b) || (b <= p && p <= a);
 	}
 }
 
-impl fmt::Debug for Segment {
+fn clamp<T: Scalar> (t: T) -> T {
+	if t < T::zero() { T::zero() } else if t > T::one() { T::one() } else { t }
+}
+
+// is t in [0, 1], or no more than the tolerance distance past either end
+// of something this long
+pub(crate) fn within<T: Scalar> (t: &T, length: T, tolerance: &Tolerance<T>) -> bool {
+	let past_a = -t.clone() * length.clone();
+	let past_b = (t.clone() - T::one()) * length;
+	return past_a <= tolerance.distance && past_b <= tolerance.distance;
+}
+
+// where the line through a in this direction meets the line, as a multiple
+// of the direction, if it isn't parallel
+pub(crate) fn line_parameter<T: Scalar> (
+	a: &Vector<T>,
+	direction: &Vector<T>,
+	l: &Line<T>,
+	tolerance: &Tolerance<T>
+) -> Option<T> {
+	if direction.parallel_with(l.u.rotate90(), tolerance) { return None; }
+	return Some((l.d.clone() - a.dot(l.u.clone())) / direction.dot(l.u.clone()));
+}
+
+impl<T: fmt::Debug> fmt::Debug for Segment<T> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("Segment")
 			.field("x1", &self.a.x)
@@ -43,4 +139,3 @@ impl fmt::Debug for Segment {
 			.finish()
 	}
 }
-
@@ -0,0 +1,43 @@
+// how close is close enough, for the geometry and the axioms. the default
+// comes from the number type (Scalar::epsilon) and suits paper about the
+// size of a unit square, use for_size for much bigger or smaller paper.
+use super::scalar::Scalar;
+use super::polynomial::Settings;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tolerance<T = f64> {
+	// points this close together are the same point, in the paper's units
+	pub distance: T,
+	// directions this close to parallel are parallel, as the sine of the angle
+	pub angle: T,
+	// roots of a polynomial this close together are the same (multiple) root
+	pub multiplicity: f64
+}
+
+impl<T: Scalar> Default for Tolerance<T> {
+	fn default () -> Tolerance<T> {
+		Tolerance {
+			distance: T::epsilon(),
+			angle: T::epsilon(),
+			multiplicity: Settings::default().merge
+		}
+	}
+}
+
+impl<T: Scalar> Tolerance<T> {
+	// the default tolerance, with distances scaled to paper of this size.
+	// angles don't depend on the size of the paper.
+	pub fn for_size (size: T) -> Tolerance<T> {
+		let tolerance = Tolerance::default();
+		let multiplicity = tolerance.multiplicity * size.to_f64().abs();
+		return Tolerance { distance: tolerance.distance * size.abs(), multiplicity, ..tolerance };
+	}
+	// is this distance (or coordinate difference) zero
+	pub fn zero (&self, x: T) -> bool { x.abs() <= self.distance }
+	// the sine of an angle, given as the determinant of two vectors and
+	// their squared lengths. squared, so that no square root is needed
+	pub fn parallel (&self, determinant: T, length_a_sq: T, length_b_sq: T) -> bool {
+		determinant.clone() * determinant
+			<= self.angle.clone() * self.angle.clone() * length_a_sq * length_b_sq
+	}
+}
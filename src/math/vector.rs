@@ -1,51 +1,56 @@
 use std::fmt;
 // use std::iter::FromIterator;
-use super::common::EPSILON;
+use std::f64::consts::PI;
+use super::scalar::Scalar;
+use super::tolerance::Tolerance;
 
-#[derive(Copy, Clone)]
-pub struct Vector {
-	pub x: f64,
-	pub y: f64
+#[derive(Copy, Clone, PartialEq)]
+pub struct Vector<T = f64> {
+	pub x: T,
+	pub y: T
 }
 
-impl Vector {
-	// returns f64
-	pub fn magnitude (&self) -> f64 { (self.x * self.x + self.y * self.y).sqrt() }
-	pub fn magnitude_squared (&self) -> f64 { self.x * self.x + self.y * self.y }
-	pub fn dot (&self, u: Vector) -> f64 { self.x * u.x + self.y * u.y }
-	pub fn determinant (&self, u: Vector) -> f64 { self.x * u.y - self.y * u.x }
-	pub fn distance_to (&self, u: Vector) -> f64 {
-		Vector { x: self.x - u.x, y: self.y - u.y }.magnitude()
-	}
+impl<T: Scalar> Vector<T> {
+	// returns a number
+	pub fn magnitude (&self) -> T { self.magnitude_squared().sqrt() }
+	pub fn magnitude_squared (&self) -> T { self.dot(self.clone()) }
+	pub fn dot (&self, u: Vector<T>) -> T { self.x.clone() * u.x + self.y.clone() * u.y }
+	pub fn determinant (&self, u: Vector<T>) -> T { self.x.clone() * u.y - self.y.clone() * u.x }
+	pub fn distance_to (&self, u: Vector<T>) -> T { self.subtract(u).magnitude() }
 	// returns vector
-	pub fn normalize (&self) -> Vector {
+	pub fn normalize (&self) -> Vector<T> {
 		let mut m = self.magnitude();
-		if m < EPSILON { m = 1.0; }
-		return Vector { x: self.x / m, y: self.y / m };
+		if m <= T::epsilon() { m = T::one(); }
+		return Vector { x: self.x.clone() / m.clone(), y: self.y.clone() / m };
 	}
-	pub fn scale (&self, t: f64) -> Vector {
-		Vector { x: self.x * t, y: self.y * t }
+	pub fn scale (&self, t: T) -> Vector<T> {
+		Vector { x: self.x.clone() * t.clone(), y: self.y.clone() * t }
 	}
-	pub fn add (&self, u: Vector) -> Vector {
-		Vector { x: self.x + u.x, y: self.y + u.y }
+	pub fn add (&self, u: Vector<T>) -> Vector<T> {
+		Vector { x: self.x.clone() + u.x, y: self.y.clone() + u.y }
 	}
-	pub fn subtract (&self, u: Vector) -> Vector {
-		Vector { x: self.x - u.x, y: self.y - u.y }
+	pub fn subtract (&self, u: Vector<T>) -> Vector<T> {
+		Vector { x: self.x.clone() - u.x, y: self.y.clone() - u.y }
 	}
-	pub fn flip (&self) -> Vector { Vector { x: -self.x, y: -self.y } }
-	pub fn rotate90 (&self) -> Vector { Vector { x: -self.y, y: self.x } }
-	pub fn rotate270 (&self) -> Vector { Vector { x: self.y, y: -self.x } }
-	pub fn midpoint (&self, u: Vector) -> Vector {
-		Vector { x: (self.x + u.x) / 2.0, y: (self.y + u.y) / 2.0 }
+	pub fn flip (&self) -> Vector<T> { Vector { x: -self.x.clone(), y: -self.y.clone() } }
+	pub fn rotate90 (&self) -> Vector<T> { Vector { x: -self.y.clone(), y: self.x.clone() } }
+	pub fn rotate270 (&self) -> Vector<T> { Vector { x: self.y.clone(), y: -self.x.clone() } }
+	pub fn midpoint (&self, u: Vector<T>) -> Vector<T> {
+		let two = T::one() + T::one();
+		Vector { x: (self.x.clone() + u.x) / two.clone(), y: (self.y.clone() + u.y) / two }
 	}
 	// returns bool
-	pub fn degenerate (&self) -> bool { (self.x.abs() + self.y.abs()) < EPSILON }
-	pub fn equivalent (&self, u: Vector) -> bool {
-		self.x - u.x < EPSILON && self.x - u.x > -EPSILON &&
-		self.y - u.y < EPSILON && self.y - u.y > -EPSILON
+	pub fn degenerate (&self) -> bool { self.degenerate_with(&Tolerance::default()) }
+	pub fn equivalent (&self, u: Vector<T>) -> bool { self.equivalent_with(u, &Tolerance::default()) }
+	pub fn parallel (&self, u: Vector<T>) -> bool { self.parallel_with(u, &Tolerance::default()) }
+	pub fn degenerate_with (&self, tolerance: &Tolerance<T>) -> bool {
+		tolerance.zero(self.x.abs() + self.y.abs())
 	}
-	pub fn parallel (&self, u: Vector) -> bool {
-		(1.0 - self.normalize().dot(u.normalize()).abs()) < EPSILON
+	pub fn equivalent_with (&self, u: Vector<T>, tolerance: &Tolerance<T>) -> bool {
+		tolerance.zero(self.x.clone() - u.x) && tolerance.zero(self.y.clone() - u.y)
+	}
+	pub fn parallel_with (&self, u: Vector<T>, tolerance: &Tolerance<T>) -> bool {
+		tolerance.parallel(self.determinant(u.clone()), self.magnitude_squared(), u.magnitude_squared())
 	}
 	// fn lerp (&self, u: Vector, t: f64) -> Vector {
 	// 	let s = 1.0 - t;
@@ -53,7 +58,17 @@ impl Vector {
 	// }
 }
 
-impl fmt::Debug for Vector {
+impl Vector {
+	// the angle from the +x axis, between -pi and pi
+	pub fn angle (&self) -> f64 { self.y.atan2(self.x) }
+	// the counter-clockwise angle from this vector to u, between 0 and 2 pi
+	pub fn angle_to (&self, u: Vector) -> f64 {
+		let a = self.determinant(u).atan2(self.dot(u));
+		if a < 0.0 { a + 2.0 * PI } else { a }
+	}
+}
+
+impl<T: fmt::Debug> fmt::Debug for Vector<T> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("Vector")
 			.field("x", &self.x)
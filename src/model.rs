@@ -0,0 +1,346 @@
+// a folded piece of paper: the crease pattern, where each of its vertices
+// is when folded, and the order the faces are stacked in. the model is
+// looked at from above, layer 0 is the bottom. folding it along a line
+// goes through every layer, or only through the flap on top, and the new
+// creases are added to the crease pattern.
+use std::fmt;
+use std::collections::HashMap;
+use math::Vector;
+use math::Line;
+use math::EPSILON;
+use math::signed_area;
+use math::winding;
+use math::triangulate;
+use graph::Graph;
+use graph::Assignment;
+use folded::vertices_coords_folded;
+use layer;
+use layer::FaceOrders;
+use layer::LayerError;
+
+// the side of a line: the side its normal points towards, or the other
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Side {
+	Positive,
+	Negative
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlapError {
+	// the touch point isn't on any face
+	NotOnPaper,
+	// the touch point is on the fold line, it doesn't say which side folds
+	OnLine,
+	// (edge) the flap is joined to paper which isn't folding by this edge
+	Tear(usize),
+	// (face) a face which isn't folding is in the way of the flap
+	Blocked(usize)
+}
+
+impl fmt::Display for FlapError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			FlapError::NotOnPaper => write!(f, "the touch point is not on the paper"),
+			FlapError::OnLine => write!(f, "the touch point is on the fold line"),
+			FlapError::Tear(edge) => write!(f, "the fold would tear edge {}", edge),
+			FlapError::Blocked(face) => write!(f, "face {} is in the way of the fold", face)
+		}
+	}
+}
+
+impl ::std::error::Error for FlapError {}
+
+// what a fold needs to know about the faces from before they were split
+struct Pieces {
+	// (edge, the old face it splits)
+	creases: Vec<(usize, usize)>,
+	// the old face each new face is a piece of
+	faces_old: Vec<usize>,
+	old_flipped: Vec<bool>,
+	old_layers: Vec<usize>
+}
+
+#[derive(Debug, Clone)]
+pub struct Model {
+	pub crease_pattern: Graph,
+	pub vertices_coords_folded: Vec<Vector>,
+	// the layer of each face of the crease pattern, 0 at the bottom
+	pub faces_layer: Vec<usize>
+}
+
+// a point inside a polygon, in either winding
+fn interior_point (polygon: &[Vector]) -> Option<Vector> {
+	triangulate(polygon).first().map(|[a, b, c]| a.add(*b).add(*c).scale(1.0 / 3.0))
+}
+
+impl Model {
+	// fold a crease pattern, keeping the first face in place
+	pub fn new (crease_pattern: Graph) -> Result<Model, LayerError> {
+		let folded = vertices_coords_folded(&crease_pattern, 0);
+		let orders = layer::solve(&crease_pattern, &folded)?;
+		let mut model = Model {
+			faces_layer: vec![0; crease_pattern.faces_vertices.len()],
+			crease_pattern,
+			vertices_coords_folded: folded
+		};
+		model.set_face_orders(&orders);
+		return Ok(model);
+	}
+	pub fn face_polygon_folded (&self, face: usize) -> Vec<Vector> {
+		self.crease_pattern.faces_vertices[face].iter().map(|v| self.vertices_coords_folded[*v]).collect()
+	}
+	// is the face upside down when folded
+	pub fn face_flipped (&self, face: usize) -> bool {
+		signed_area(&self.face_polygon_folded(face)) < 0.0
+	}
+	// the stacking order in the FOLD format, for each pair of overlapping faces
+	pub fn face_orders (&self) -> FaceOrders {
+		layer::overlapping_faces(&self.crease_pattern, &self.vertices_coords_folded).iter()
+			.map(|(f, g)| {
+				let above = self.faces_layer[*f] > self.faces_layer[*g];
+				(*f, *g, if above != self.face_flipped(*g) { 1 } else { -1 })
+			})
+			.collect()
+	}
+	// turn the orders of the pairs of faces into one layer for each face,
+	// placing a face as soon as every face below it has been placed
+	pub fn set_face_orders (&mut self, orders: &[(usize, usize, i8)]) {
+		let count = self.crease_pattern.faces_vertices.len();
+		let mut below: Vec<Vec<usize>> = vec![vec![]; count];
+		for (f, g, s) in orders.iter() {
+			let f_above = (*s == 1) != self.face_flipped(*g);
+			if f_above { below[*f].push(*g); } else { below[*g].push(*f); }
+		}
+		let mut placed = vec![false; count];
+		let mut layer = 0;
+		while layer < count {
+			let next = (0..count).find(|f| !placed[*f] && below[*f].iter().all(|g| placed[*g]));
+			// a cycle can't be stacked, place the rest in order
+			let f = next.unwrap_or_else(|| (0..count).find(|f| !placed[*f]).unwrap());
+			placed[f] = true;
+			self.faces_layer[f] = layer;
+			layer += 1;
+		}
+	}
+	// fold every layer along the line (in the folded coordinates). the faces
+	// on one side of the line are reflected over to the other. a valley
+	// brings them up on top of the stack, a mountain folds them underneath.
+	// any other assignment only marks the line on the paper.
+	// @returns the edges of the crease pattern that were folded
+	pub fn flat_fold (&mut self, line: Line, assignment: Assignment, which_side: Side) -> Vec<usize> {
+		let all = vec![true; self.crease_pattern.faces_vertices.len()];
+		let pieces = self.split(line, &all);
+		let moving: Vec<bool> = (0..self.crease_pattern.faces_vertices.len())
+			.map(|f| self.on_side(f, line, which_side))
+			.collect();
+		return self.finish(line, assignment, &pieces, &moving);
+	}
+	// fold only the flap under the touch point: the top face there, and all
+	// of the paper connected to it on the touch point's side of the line.
+	// the layers underneath stay in place and only the flap gets new creases.
+	// nothing changes if the fold would tear the paper, or if the flap
+	// would have to pass through a face which isn't folding with it.
+	// @returns the edges of the crease pattern that were folded
+	pub fn fold_flap (&mut self, line: Line, assignment: Assignment, touch: Vector) -> Result<Vec<usize>, FlapError> {
+		let distance = touch.dot(line.u) - line.d;
+		if distance.abs() < EPSILON { return Err(FlapError::OnLine); }
+		let which_side = if distance > 0.0 { Side::Positive } else { Side::Negative };
+		let on_side = |p: Vector| match which_side {
+			Side::Positive => p.dot(line.u) - line.d > EPSILON,
+			Side::Negative => p.dot(line.u) - line.d < -EPSILON
+		};
+		let count = self.crease_pattern.faces_vertices.len();
+		let touched = (0..count)
+			.filter(|f| winding(&self.face_polygon_folded(*f), touch) != 0)
+			.max_by_key(|f| self.faces_layer[*f])
+			.ok_or(FlapError::NotOnPaper)?;
+		// the flap spreads across any edge which reaches the moving side
+		let edges_faces = self.crease_pattern.edges_faces();
+		let faces_edges = self.crease_pattern.faces_edges();
+		let mut flap = vec![false; count];
+		flap[touched] = true;
+		let mut stack = vec![touched];
+		while let Some(f) = stack.pop() {
+			for e in faces_edges[f].iter() {
+				let [a, b] = self.crease_pattern.edges_vertices[*e];
+				let (p, q) = (self.vertices_coords_folded[a], self.vertices_coords_folded[b]);
+				if !on_side(p) && !on_side(q) && !on_side(p.midpoint(q)) { continue; }
+				for g in edges_faces[*e].iter() {
+					if !flap[*g] {
+						flap[*g] = true;
+						stack.push(*g);
+					}
+				}
+			}
+		}
+		let mut model = self.clone();
+		let pieces = model.split(line, &flap);
+		let moving: Vec<bool> = (0..model.crease_pattern.faces_vertices.len())
+			.map(|f| flap[pieces.faces_old[f]] && model.on_side(f, line, which_side))
+			.collect();
+		// a moving face can only be joined to a staying face by the fold line
+		for (e, faces) in model.crease_pattern.edges_faces().iter().enumerate() {
+			if faces.len() != 2 || moving[faces[0]] == moving[faces[1]] { continue; }
+			let [a, b] = model.crease_pattern.edges_vertices[e];
+			let on_line = |v: usize| (model.vertices_coords_folded[v].dot(line.u) - line.d).abs() < EPSILON;
+			if !on_line(a) || !on_line(b) { return Err(FlapError::Tear(e)); }
+		}
+		// a valley swings the flap up, a mountain swings it down,
+		// a face in the way which isn't folding would be pierced
+		let upward = assignment != Assignment::Mountain;
+		for (f, g) in layer::overlapping_faces(&model.crease_pattern, &model.vertices_coords_folded) {
+			if moving[f] == moving[g] { continue; }
+			let (flap_face, other) = if moving[f] { (f, g) } else { (g, f) };
+			let above = model.faces_layer[other] > model.faces_layer[flap_face];
+			if above == upward { return Err(FlapError::Blocked(pieces.faces_old[other])); }
+		}
+		// and where the flap lands, a layer which isn't part of the flap on the
+		// side it swings through would have to be wrapped inside the fold
+		let mut landed = model.vertices_coords_folded.clone();
+		for (f, face) in model.crease_pattern.faces_vertices.iter().enumerate() {
+			if !moving[f] { continue; }
+			for v in face.iter() {
+				if (landed[*v].dot(line.u) - line.d).abs() < EPSILON { continue; }
+				landed[*v] = line.reflect_vector(model.vertices_coords_folded[*v]);
+			}
+		}
+		for (f, g) in layer::overlapping_faces(&model.crease_pattern, &landed) {
+			if moving[f] == moving[g] { continue; }
+			let (flap_face, other) = if moving[f] { (f, g) } else { (g, f) };
+			if flap[pieces.faces_old[other]] { continue; }
+			let above = model.faces_layer[other] > model.faces_layer[flap_face];
+			if above == upward { return Err(FlapError::Blocked(pieces.faces_old[other])); }
+		}
+		let creases = model.finish(line, assignment, &pieces, &moving);
+		*self = model;
+		return Ok(creases);
+	}
+	// is the face on this side of the line, when folded
+	fn on_side (&self, face: usize, line: Line, which_side: Side) -> bool {
+		let s = interior_point(&self.face_polygon_folded(face))
+			.map(|p| p.dot(line.u) - line.d)
+			.unwrap_or(0.0);
+		match which_side { Side::Positive => s > 0.0, Side::Negative => s < 0.0 }
+	}
+	// split the faces (as marked in the list) which cross the line. the edges
+	// start out flat, they are assigned once it's known which pieces move.
+	fn split (&mut self, line: Line, faces: &[bool]) -> Pieces {
+		let side = |p: Vector| p.dot(line.u) - line.d;
+		let old_layers = self.faces_layer.clone();
+		let graph = &mut self.crease_pattern;
+		let folded = &mut self.vertices_coords_folded;
+		let old_polygons: Vec<Vec<Vector>> = (0..graph.faces_vertices.len())
+			.map(|f| graph.face_polygon(f))
+			.collect();
+		let old_flipped: Vec<bool> = graph.faces_vertices.iter()
+			.map(|face| signed_area(&face.iter().map(|v| folded[*v]).collect::<Vec<Vector>>()) < 0.0)
+			.collect();
+		// split every edge which crosses the line. affine maps keep the
+		// parameter along the edge, so the crease pattern splits at the same place
+		let edges_faces = graph.edges_faces();
+		for (e, edge_faces) in edges_faces.iter().enumerate() {
+			if !edge_faces.iter().any(|f| faces[*f]) { continue; }
+			let [a, b] = graph.edges_vertices[e];
+			let (side_a, side_b) = (side(folded[a]), side(folded[b]));
+			if (side_a < -EPSILON && side_b > EPSILON) || (side_a > EPSILON && side_b < -EPSILON) {
+				let t = side_a / (side_a - side_b);
+				let lerp = |p: Vector, q: Vector| p.add(q.subtract(p).scale(t));
+				let point = lerp(graph.vertices_coords[a], graph.vertices_coords[b]);
+				let folded_point = lerp(folded[a], folded[b]);
+				graph.split_edge(e, point);
+				folded.push(folded_point);
+			}
+		}
+		// inside every face, join the vertices on the line
+		let mut creases = vec![];
+		for (f, polygon) in old_polygons.iter().enumerate() {
+			if !faces[f] { continue; }
+			let face = &graph.faces_vertices[f];
+			let mut on_line: Vec<usize> = face.iter()
+				.filter(|v| side(folded[**v]).abs() < EPSILON)
+				.cloned()
+				.collect();
+			let direction = line.u.rotate90();
+			on_line.sort_by(|a, b| folded[*a].dot(direction).partial_cmp(&folded[*b].dot(direction)).unwrap());
+			on_line.dedup();
+			for pair in on_line.windows(2) {
+				if graph.edge_between(pair[0], pair[1]).is_some() { continue; }
+				let middle = graph.vertices_coords[pair[0]].midpoint(graph.vertices_coords[pair[1]]);
+				if winding(polygon, middle) == 0 { continue; }
+				graph.edges_vertices.push([pair[0], pair[1]]);
+				graph.edges_assignment.push(Assignment::Flat);
+				creases.push((graph.edges_vertices.len() - 1, f));
+			}
+		}
+		// the new faces are pieces of the old faces
+		graph.rebuild_faces();
+		let faces_old: Vec<usize> = (0..graph.faces_vertices.len())
+			.map(|f| interior_point(&graph.face_polygon(f))
+				.and_then(|p| old_polygons.iter().position(|polygon| winding(polygon, p) != 0))
+				.unwrap_or(0))
+			.collect();
+		self.faces_layer = faces_old.iter().map(|f| old_layers[*f]).collect();
+		return Pieces { creases, faces_old, old_flipped, old_layers };
+	}
+	// assign the creases, reflect the moving faces and restack the layers
+	fn finish (&mut self, line: Line, assignment: Assignment, pieces: &Pieces, moving: &[bool]) -> Vec<usize> {
+		let side = |p: Vector| p.dot(line.u) - line.d;
+		let graph = &mut self.crease_pattern;
+		let folded = &mut self.vertices_coords_folded;
+		let faces_old = &pieces.faces_old;
+		// the creases are seen from above, a face which is upside down
+		// sees a mountain where the model has a valley
+		let crease = |flipped: bool| match assignment {
+			Assignment::Mountain | Assignment::Valley if flipped => assignment.flip(),
+			_ => assignment
+		};
+		for (e, f) in pieces.creases.iter() {
+			graph.edges_assignment[*e] = crease(pieces.old_flipped[*f]);
+		}
+		let mut creases: Vec<usize> = pieces.creases.iter().map(|(e, _)| *e).collect();
+		// edges already on the line, between a moving and a staying face
+		for (e, faces) in graph.edges_faces().iter().enumerate() {
+			if faces.len() != 2 || creases.contains(&e) || moving[faces[0]] == moving[faces[1]] { continue; }
+			if graph.edges_assignment[e].is_boundary() { continue; }
+			let [a, b] = graph.edges_vertices[e];
+			if side(folded[a]).abs() > EPSILON || side(folded[b]).abs() > EPSILON { continue; }
+			let staying = if moving[faces[0]] { faces[1] } else { faces[0] };
+			graph.edges_assignment[e] = crease(pieces.old_flipped[faces_old[staying]]);
+			creases.push(e);
+		}
+		if assignment != Assignment::Mountain && assignment != Assignment::Valley { return creases; }
+		// reflect the moving side
+		let mut reflected = vec![false; folded.len()];
+		for (f, face) in graph.faces_vertices.iter().enumerate() {
+			if !moving[f] { continue; }
+			for v in face.iter() {
+				if reflected[*v] || side(folded[*v]).abs() < EPSILON { continue; }
+				folded[*v] = line.reflect_vector(folded[*v]);
+				reflected[*v] = true;
+			}
+		}
+		// the moving faces turn over: their order reverses, and they go
+		// on top for a valley or underneath for a mountain
+		let mut staying: Vec<usize> = (0..moving.len()).filter(|f| !moving[*f]).collect();
+		let mut turning: Vec<usize> = (0..moving.len()).filter(|f| moving[*f]).collect();
+		staying.sort_by_key(|f| pieces.old_layers[faces_old[*f]]);
+		turning.sort_by_key(|f| std::cmp::Reverse(pieces.old_layers[faces_old[*f]]));
+		let stack: Vec<usize> = if assignment == Assignment::Valley {
+			staying.into_iter().chain(turning).collect()
+		} else {
+			turning.into_iter().chain(staying).collect()
+		};
+		let mut layers: HashMap<usize, usize> = HashMap::new();
+		for (layer, f) in stack.iter().enumerate() { layers.insert(*f, layer); }
+		self.faces_layer = (0..moving.len()).map(|f| layers[&f]).collect();
+		return creases;
+	}
+}
+
+impl From<Graph> for Model {
+	// an unfolded sheet of paper, every face in place and side by side
+	fn from (graph: Graph) -> Model {
+		let folded = graph.vertices_coords.clone();
+		Model { faces_layer: vec![0; graph.faces_vertices.len()], crease_pattern: graph, vertices_coords_folded: folded }
+	}
+}
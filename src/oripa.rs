@@ -0,0 +1,168 @@
+// reading and writing the two file formats of ORIPA, the crease pattern
+// editor by Jun Mitani: .opx (XML) and .cp (plain text, one line per crease)
+// ORIPA's paper is a square centered at the origin, 400 units wide unless
+// the file says otherwise. coordinates are converted to and from the unit
+// square of make_square, x and y are not flipped.
+use std::fmt;
+use roxmltree;
+use math::Vector;
+use math::Segment;
+use graph::Assignment;
+
+pub const PAPER_SIZE: f64 = 400.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OripaError {
+	// (line number starting at 1, the problem) for .cp files
+	Line(usize, String),
+	// the text is not valid XML
+	Xml(String),
+	// an .opx value couldn't be understood
+	InvalidValue(String)
+}
+
+impl fmt::Display for OripaError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			OripaError::Line(line, ref e) => write!(f, "line {}: {}", line, e),
+			OripaError::Xml(ref e) => write!(f, "invalid XML: {}", e),
+			OripaError::InvalidValue(ref e) => write!(f, "invalid value: {}", e)
+		}
+	}
+}
+
+impl ::std::error::Error for OripaError {}
+
+// ORIPA line types: 0 auxiliary, 1 cut (the paper edge), 2 mountain, 3 valley
+fn assignment_from_type (line_type: i64) -> Option<Assignment> {
+	match line_type {
+		0 => Some(Assignment::Flat),
+		1 => Some(Assignment::Boundary),
+		2 => Some(Assignment::Mountain),
+		3 => Some(Assignment::Valley),
+		_ => None
+	}
+}
+
+// ORIPA has no unassigned or cut lines. unassigned lines become
+// auxiliary lines, cuts become part of the paper's edge.
+fn type_from_assignment (assignment: Assignment) -> i64 {
+	match assignment {
+		Assignment::Flat | Assignment::Unassigned => 0,
+		Assignment::Boundary | Assignment::Cut => 1,
+		Assignment::Mountain => 2,
+		Assignment::Valley => 3
+	}
+}
+
+fn from_oripa (x: f64, y: f64, size: f64) -> Vector {
+	Vector { x: x / size + 0.5, y: y / size + 0.5 }
+}
+
+fn to_oripa (p: Vector, size: f64) -> (f64, f64) {
+	((p.x - 0.5) * size, (p.y - 0.5) * size)
+}
+
+// each line is "type x1 y1 x2 y2", blank lines are skipped
+pub fn parse_cp (text: &str) -> Result<Vec<(Segment, Assignment)>, OripaError> {
+	let mut segments = vec![];
+	for (i, line) in text.lines().enumerate() {
+		if line.trim().is_empty() { continue; }
+		let error = |e: &str| OripaError::Line(i + 1, e.to_string());
+		let values: Vec<f64> = line.split_whitespace()
+			.map(|s| s.parse::<f64>().map_err(|_| error(&format!("\"{}\" is not a number", s))))
+			.collect::<Result<_, _>>()?;
+		if values.len() != 5 { return Err(error("expected \"type x1 y1 x2 y2\"")); }
+		if values[0].fract() != 0.0 { return Err(error("the line type must be an integer")); }
+		let assignment = assignment_from_type(values[0] as i64)
+			.ok_or_else(|| error("the line type must be 0, 1, 2, or 3"))?;
+		let a = from_oripa(values[1], values[2], PAPER_SIZE);
+		let b = from_oripa(values[3], values[4], PAPER_SIZE);
+		segments.push((Segment { a, b }, assignment));
+	}
+	return Ok(segments);
+}
+
+pub fn write_cp (segments: &[(Segment, Assignment)]) -> String {
+	segments.iter()
+		.map(|(s, assignment)| {
+			let (x1, y1) = to_oripa(s.a, PAPER_SIZE);
+			let (x2, y2) = to_oripa(s.b, PAPER_SIZE);
+			format!("{} {} {} {} {}\n", type_from_assignment(*assignment), x1, y1, x2, y2)
+		})
+		.collect()
+}
+
+// the value inside a <void property="name"> element, like <double>1.0</double>
+fn property<'a> (object: roxmltree::Node<'a, 'a>, name: &str) -> Option<roxmltree::Node<'a, 'a>> {
+	object.children()
+		.filter(|n| n.has_tag_name("void") && n.attribute("property") == Some(name))
+		.flat_map(|n| n.children().filter(|c| c.is_element()))
+		.next()
+}
+
+fn number_property (object: roxmltree::Node, name: &str) -> Result<f64, OripaError> {
+	match property(object, name) {
+		// java's XMLEncoder leaves out properties which are the default, 0
+		None => Ok(0.0),
+		Some(value) => {
+			let text = value.text().unwrap_or("").trim();
+			text.parse::<f64>()
+				.map_err(|_| OripaError::InvalidValue(format!("{} \"{}\"", name, text)))
+		}
+	}
+}
+
+pub fn parse_opx (text: &str) -> Result<Vec<(Segment, Assignment)>, OripaError> {
+	let document = roxmltree::Document::parse(text).map_err(|e| OripaError::Xml(e.to_string()))?;
+	let data_set = document.descendants()
+		.find(|n| n.has_tag_name("object") && n.attribute("class") == Some("oripa.DataSet"))
+		.ok_or_else(|| OripaError::InvalidValue("there is no oripa.DataSet".to_string()))?;
+	let size = match number_property(data_set, "paperSize")? {
+		s if s > 0.0 => s,
+		_ => PAPER_SIZE
+	};
+	let mut segments = vec![];
+	for line in data_set.descendants()
+		.filter(|n| n.has_tag_name("object") && n.attribute("class") == Some("oripa.OriLineProxy")) {
+		let line_type = number_property(line, "type")?;
+		let assignment = assignment_from_type(line_type as i64)
+			.filter(|_| line_type.fract() == 0.0)
+			.ok_or_else(|| OripaError::InvalidValue(format!("type \"{}\"", line_type)))?;
+		let a = from_oripa(number_property(line, "x0")?, number_property(line, "y0")?, size);
+		let b = from_oripa(number_property(line, "x1")?, number_property(line, "y1")?, size);
+		segments.push((Segment { a, b }, assignment));
+	}
+	return Ok(segments);
+}
+
+pub fn write_opx (segments: &[(Segment, Assignment)]) -> String {
+	let mut opx = String::new();
+	opx += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+	opx += "<java version=\"1.5.0_05\" class=\"java.beans.XMLDecoder\">\n";
+	opx += " <object class=\"oripa.DataSet\">\n";
+	opx += "  <void property=\"lines\">\n";
+	opx += &format!("   <array class=\"oripa.OriLineProxy\" length=\"{}\">\n", segments.len());
+	for (i, (s, assignment)) in segments.iter().enumerate() {
+		let (x0, y0) = to_oripa(s.a, PAPER_SIZE);
+		let (x1, y1) = to_oripa(s.b, PAPER_SIZE);
+		opx += &format!("    <void index=\"{}\">\n", i);
+		opx += "     <object class=\"oripa.OriLineProxy\">\n";
+		opx += &format!("      <void property=\"type\">\n       <int>{}</int>\n      </void>\n",
+			type_from_assignment(*assignment));
+		for (name, value) in [("x0", x0), ("x1", x1), ("y0", y0), ("y1", y1)].iter() {
+			opx += &format!("      <void property=\"{}\">\n       <double>{:?}</double>\n      </void>\n",
+				name, value);
+		}
+		opx += "     </object>\n";
+		opx += "    </void>\n";
+	}
+	opx += "   </array>\n";
+	opx += "  </void>\n";
+	opx += "  <void property=\"mainVersion\">\n   <int>1</int>\n  </void>\n";
+	opx += &format!("  <void property=\"paperSize\">\n   <double>{:?}</double>\n  </void>\n", PAPER_SIZE);
+	opx += "  <void property=\"subVersion\">\n   <int>1</int>\n  </void>\n";
+	opx += " </object>\n";
+	opx += "</java>\n";
+	return opx;
+}
@@ -0,0 +1,162 @@
+// turn a pile of segments (which can cross, overlap, and nearly touch)
+// into a clean planar graph: every crossing becomes a vertex, collinear
+// overlapping segments become one edge, and points closer together than
+// the tolerance distance are welded into one vertex. segments closer to
+// parallel than the tolerance angle can only touch at their ends.
+use std::collections::HashMap;
+use math::Vector;
+use math::Segment;
+use math::Tolerance;
+use graph::Graph;
+use graph::Assignment;
+
+// when two segments overlap, which assignment does the edge keep?
+// the paper's edge wins over everything, then mountain and valley win
+// over flat and unassigned. a mountain on top of a valley can't be
+// decided, the edge becomes unassigned.
+pub fn resolve_assignment (a: Assignment, b: Assignment) -> Assignment {
+	let rank = |x: Assignment| match x {
+		Assignment::Boundary => 4,
+		Assignment::Cut => 3,
+		Assignment::Mountain | Assignment::Valley => 2,
+		Assignment::Unassigned => 1,
+		Assignment::Flat => 0
+	};
+	if a == b { return a; }
+	if rank(a) == rank(b) { return Assignment::Unassigned; }
+	return if rank(a) > rank(b) { a } else { b };
+}
+
+// union-find with path compression, for welding points
+fn find (parents: &mut [usize], i: usize) -> usize {
+	let mut root = i;
+	while parents[root] != root { root = parents[root]; }
+	let mut node = i;
+	while parents[node] != root {
+		let next = parents[node];
+		parents[node] = root;
+		node = next;
+	}
+	return root;
+}
+
+// the places along segment a (as parameters from 0 to 1) where b touches it,
+// and the same for b along a.
+fn touches (a: Segment, b: Segment, tolerance: &Tolerance) -> (Vec<f64>, Vec<f64>) {
+	let vec_a = a.b.subtract(a.a);
+	let vec_b = b.b.subtract(b.a);
+	let len_a = vec_a.magnitude();
+	let len_b = vec_b.magnitude();
+	// the parameter of a point projected onto a segment, if it is
+	// within the tolerance of the segment
+	let project = |s: Segment, vec: Vector, len: f64, p: Vector| {
+		let t = p.subtract(s.a).dot(vec) / (len * len);
+		let distance = s.a.add(vec.scale(t)).distance_to(p);
+		let pad = tolerance.distance / len;
+		if tolerance.zero(distance) && t >= -pad && t <= 1.0 + pad { Some(t.clamp(0.0, 1.0)) } else { None }
+	};
+	// parallel (or nearly), including collinear overlaps: only the endpoints
+	// of one segment can lie on the other
+	if vec_a.parallel_with(vec_b, tolerance) {
+		let on_a = [b.a, b.b].iter().filter_map(|p| project(a, vec_a, len_a, *p)).collect();
+		let on_b = [a.a, a.b].iter().filter_map(|p| project(b, vec_b, len_b, *p)).collect();
+		return (on_a, on_b);
+	}
+	let det = vec_a.determinant(vec_b);
+	let offset = b.a.subtract(a.a);
+	let t = offset.determinant(vec_b) / det;
+	let s = offset.determinant(vec_a) / det;
+	let pad_a = tolerance.distance / len_a;
+	let pad_b = tolerance.distance / len_b;
+	if t < -pad_a || t > 1.0 + pad_a || s < -pad_b || s > 1.0 + pad_b {
+		return (vec![], vec![]);
+	}
+	return (vec![t.clamp(0.0, 1.0)], vec![s.clamp(0.0, 1.0)]);
+}
+
+pub fn planarize (segments: &[(Segment, Assignment)], tolerance: &Tolerance) -> Graph {
+	// a segment no longer than the tolerance is a point, not an edge
+	let segments: Vec<(Segment, Assignment)> = segments.iter()
+		.filter(|(s, _)| !tolerance.zero(s.a.distance_to(s.b)))
+		.cloned()
+		.collect();
+	// every segment is cut at its endpoints and wherever it touches another.
+	// sort by the left end so that the pairs far apart can be skipped.
+	let mut cuts: Vec<Vec<f64>> = vec![vec![0.0, 1.0]; segments.len()];
+	let min_x = |s: &Segment| s.a.x.min(s.b.x);
+	let max_x = |s: &Segment| s.a.x.max(s.b.x);
+	let mut order: Vec<usize> = (0..segments.len()).collect();
+	order.sort_by(|a, b| min_x(&segments[*a].0).partial_cmp(&min_x(&segments[*b].0)).unwrap());
+	for (n, i) in order.iter().enumerate() {
+		let a = segments[*i].0;
+		for j in order.iter().skip(n + 1) {
+			let b = segments[*j].0;
+			if min_x(&b) > max_x(&a) + tolerance.distance { break; }
+			let (on_a, on_b) = touches(a, b, tolerance);
+			cuts[*i].extend(on_a);
+			cuts[*j].extend(on_b);
+		}
+	}
+	// every cut is a point, weld together the points that are close
+	let mut points: Vec<Vector> = vec![];
+	let mut cut_points: Vec<Vec<(f64, usize)>> = vec![];
+	for (i, (s, _)) in segments.iter().enumerate() {
+		let mut list = vec![];
+		for t in cuts[i].iter() {
+			list.push((*t, points.len()));
+			points.push(s.a.add(s.b.subtract(s.a).scale(*t)));
+		}
+		list.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		cut_points.push(list);
+	}
+	let mut parents: Vec<usize> = (0..points.len()).collect();
+	let mut by_x: Vec<usize> = (0..points.len()).collect();
+	by_x.sort_by(|a, b| points[*a].x.partial_cmp(&points[*b].x).unwrap());
+	for (n, i) in by_x.iter().enumerate() {
+		for j in by_x.iter().skip(n + 1) {
+			if points[*j].x - points[*i].x > tolerance.distance { break; }
+			if tolerance.zero(points[*i].distance_to(points[*j])) {
+				let (ri, rj) = (find(&mut parents, *i), find(&mut parents, *j));
+				if ri != rj { parents[rj.max(ri)] = rj.min(ri); }
+			}
+		}
+	}
+	// each group of welded points becomes one vertex at their average
+	let mut graph = Graph::new();
+	let mut vertex_of_root: HashMap<usize, usize> = HashMap::new();
+	let mut sums: Vec<(Vector, f64)> = vec![];
+	let mut vertex_of_point: Vec<usize> = vec![0; points.len()];
+	for (i, point) in points.iter().enumerate() {
+		let root = find(&mut parents, i);
+		let v = *vertex_of_root.entry(root).or_insert_with(|| {
+			sums.push((Vector { x: 0.0, y: 0.0 }, 0.0));
+			sums.len() - 1
+		});
+		sums[v] = (sums[v].0.add(*point), sums[v].1 + 1.0);
+		vertex_of_point[i] = v;
+	}
+	graph.vertices_coords = sums.iter().map(|(sum, count)| sum.scale(1.0 / count)).collect();
+	// walk each segment from cut to cut, overlapping pieces will have
+	// the same pair of vertices, merge them into one edge
+	let mut edge_of_pair: HashMap<(usize, usize), usize> = HashMap::new();
+	for (i, (_, assignment)) in segments.iter().enumerate() {
+		let mut vertices: Vec<usize> = cut_points[i].iter().map(|(_, p)| vertex_of_point[*p]).collect();
+		vertices.dedup();
+		for pair in vertices.windows(2) {
+			let key = if pair[0] < pair[1] { (pair[0], pair[1]) } else { (pair[1], pair[0]) };
+			match edge_of_pair.get(&key) {
+				Some(e) => {
+					let current = graph.edges_assignment[*e];
+					graph.edges_assignment[*e] = resolve_assignment(current, *assignment);
+				},
+				None => {
+					edge_of_pair.insert(key, graph.edges_vertices.len());
+					graph.edges_vertices.push([pair[0], pair[1]]);
+					graph.edges_assignment.push(*assignment);
+				}
+			}
+		}
+	}
+	graph.rebuild_faces();
+	return graph;
+}
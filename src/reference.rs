@@ -0,0 +1,268 @@
+// finding a sequence of folds which locates a point or a line, like Robert
+// Lang's ReferenceFinder. the database starts with the paper's corners and
+// edges, and each level applies all seven axioms to everything made so far
+// using one more fold. every point is where two lines cross. the rank of a
+// line is the number of folds it took to make, the rank of a point is the
+// sum of the ranks of its two lines.
+use std::collections::HashSet;
+use math::Vector;
+use math::Line;
+use math::Boundary;
+use math::EPSILON;
+use axioms;
+
+mod cache;
+pub use self::cache::CacheError;
+pub use self::cache::paper_key;
+pub use self::cache::cache_path;
+
+// where a line came from. the numbers are indices into the database's
+// points (p) and lines (l), in the order the axiom takes them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineSource {
+	// a side of the paper
+	Edge,
+	Axiom1(usize, usize),
+	Axiom2(usize, usize),
+	Axiom3(usize, usize),
+	Axiom4(usize, usize),
+	Axiom5(usize, usize, usize),
+	Axiom6(usize, usize, usize, usize),
+	Axiom7(usize, usize, usize)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PointSource {
+	// a corner of the paper
+	Corner,
+	// (line, line) where two lines cross
+	Intersection(usize, usize)
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct LineReference {
+	pub line: Line,
+	pub source: LineSource,
+	pub rank: usize
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PointReference {
+	pub point: Vector,
+	pub source: PointSource,
+	pub rank: usize
+}
+
+// a way to make a point or a line. the lines to fold are in order,
+// each one only needs the lines before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sequence {
+	// the index of the point or line which was found
+	pub index: usize,
+	pub error: f64,
+	pub folds: usize,
+	pub steps: Vec<usize>
+}
+
+#[derive(Debug, Clone)]
+pub struct Database {
+	pub points: Vec<PointReference>,
+	pub lines: Vec<LineReference>,
+	pub depth: usize,
+	point_keys: HashSet<(i64, i64)>,
+	line_keys: HashSet<(i64, i64, i64)>
+}
+
+// elements closer than this are the same element
+const RESOLUTION: f64 = 1.0e-7;
+
+fn quantize (n: f64) -> i64 { (n / RESOLUTION).round() as i64 }
+
+fn point_key (p: Vector) -> (i64, i64) { (quantize(p.x), quantize(p.y)) }
+
+// (u, d) and (-u, -d) are the same line, point u to the right (or up)
+fn line_key (l: Line) -> (i64, i64, i64) {
+	let flip = l.u.x < -EPSILON || (l.u.x.abs() <= EPSILON && l.u.y < 0.0);
+	let (u, d) = if flip { (l.u.flip(), -l.d) } else { (l.u, l.d) };
+	(quantize(u.x), quantize(u.y), quantize(d))
+}
+
+impl Database {
+	pub fn new () -> Database {
+		Database { points: vec![], lines: vec![], depth: 0, point_keys: HashSet::new(), line_keys: HashSet::new() }
+	}
+	// everything which can be made in this many folds
+	pub fn build<B: Boundary> (boundary: B, depth: usize) -> Database {
+		let mut database = Database::new();
+		for ring in boundary.rings() {
+			for (i, p) in ring.iter().enumerate() {
+				let q = ring[(i + 1) % ring.len()];
+				database.add_point(*p, PointSource::Corner, 0);
+				let u = q.subtract(*p).rotate90().normalize();
+				database.add_line(Line { u, d: p.dot(u) }, LineSource::Edge, 0);
+			}
+		}
+		for rank in 1..=depth {
+			database.add_level(&boundary, rank);
+		}
+		return database;
+	}
+	pub(crate) fn add_point (&mut self, point: Vector, source: PointSource, rank: usize) -> bool {
+		if !self.point_keys.insert(point_key(point)) { return false; }
+		self.points.push(PointReference { point, source, rank });
+		return true;
+	}
+	pub(crate) fn add_line (&mut self, line: Line, source: LineSource, rank: usize) -> bool {
+		if !self.line_keys.insert(line_key(line)) { return false; }
+		self.lines.push(LineReference { line, source, rank });
+		return true;
+	}
+	// every line and point which takes exactly this many folds
+	fn add_level<B: Boundary> (&mut self, boundary: &B, rank: usize) {
+		let parent = rank - 1;
+		let points: Vec<(usize, Vector, usize)> = self.points.iter().enumerate()
+			.filter(|(_, p)| p.rank <= parent)
+			.map(|(i, p)| (i, p.point, p.rank))
+			.collect();
+		let lines: Vec<(usize, Line, usize)> = self.lines.iter().enumerate()
+			.filter(|(_, l)| l.rank <= parent)
+			.map(|(i, l)| (i, l.line, l.rank))
+			.collect();
+		let mut found: Vec<(Line, LineSource)> = vec![];
+		for (n, (i, a, ra)) in points.iter().enumerate() {
+			for (j, b, rb) in points.iter().skip(n + 1) {
+				if ra + rb != parent { continue; }
+				for l in axioms::axiom1(*a, *b, boundary) { found.push((l, LineSource::Axiom1(*i, *j))); }
+				for l in axioms::axiom2(*a, *b, boundary) { found.push((l, LineSource::Axiom2(*i, *j))); }
+			}
+		}
+		for (n, (i, a, ra)) in lines.iter().enumerate() {
+			for (j, b, rb) in lines.iter().skip(n + 1) {
+				if ra + rb != parent { continue; }
+				for l in axioms::axiom3(*a, *b, boundary) { found.push((l, LineSource::Axiom3(*i, *j))); }
+			}
+		}
+		for (i, p, rp) in points.iter() {
+			for (j, l, rl) in lines.iter() {
+				if rp + rl == parent {
+					for s in axioms::axiom4(*p, *l, boundary) { found.push((s, LineSource::Axiom4(*i, *j))); }
+				}
+				for (k, q, rq) in points.iter() {
+					if k == i || rp + rq + rl != parent { continue; }
+					for s in axioms::axiom5(*p, *q, *l, boundary) { found.push((s, LineSource::Axiom5(*i, *k, *j))); }
+				}
+				for (k, m, rm) in lines.iter() {
+					if k == j || rp + rl + rm != parent { continue; }
+					for s in axioms::axiom7(*p, *l, *m, boundary) { found.push((s, LineSource::Axiom7(*i, *j, *k))); }
+				}
+			}
+		}
+		for (i, p, rp) in points.iter() {
+			for (j, q, rq) in points.iter() {
+				if j == i || rp + rq > parent { continue; }
+				for (k, l, rl) in lines.iter() {
+					if rp + rq + rl > parent { continue; }
+					for (m, n, rn) in lines.iter() {
+						if m == k || rp + rq + rl + rn != parent { continue; }
+						// with floats the roots are always there, this can't fail
+						for s in axioms::axiom6(*p, *q, *l, *n, boundary).unwrap_or_default() {
+							found.push((s, LineSource::Axiom6(*i, *j, *k, *m)));
+						}
+					}
+				}
+			}
+		}
+		for (line, source) in found {
+			if line.u.x.is_finite() && line.u.y.is_finite() && line.d.is_finite() {
+				self.add_line(line, source, rank);
+			}
+		}
+		// the points made with this many folds, where two lines cross
+		for a in 0..self.lines.len() {
+			for b in 0..a {
+				if self.lines[a].rank + self.lines[b].rank != rank { continue; }
+				let (success, point) = self.lines[a].line.intersect(self.lines[b].line);
+				if success && boundary.contains(point) {
+					self.add_point(point, PointSource::Intersection(a, b), rank);
+				}
+			}
+		}
+		self.depth = self.depth.max(rank);
+	}
+	// the lines it takes to make a line, in the order to fold them
+	pub fn line_steps (&self, index: usize) -> Vec<usize> {
+		let mut steps = vec![];
+		self.collect_line(index, &mut steps);
+		return steps;
+	}
+	pub fn point_steps (&self, index: usize) -> Vec<usize> {
+		let mut steps = vec![];
+		self.collect_point(index, &mut steps);
+		return steps;
+	}
+	fn collect_point (&self, index: usize, steps: &mut Vec<usize>) {
+		if let PointSource::Intersection(a, b) = self.points[index].source {
+			self.collect_line(a, steps);
+			self.collect_line(b, steps);
+		}
+	}
+	fn collect_line (&self, index: usize, steps: &mut Vec<usize>) {
+		if steps.contains(&index) { return; }
+		let (points, lines): (Vec<usize>, Vec<usize>) = match self.lines[index].source {
+			LineSource::Edge => return,
+			LineSource::Axiom1(a, b) | LineSource::Axiom2(a, b) => (vec![a, b], vec![]),
+			LineSource::Axiom3(a, b) => (vec![], vec![a, b]),
+			LineSource::Axiom4(p, l) => (vec![p], vec![l]),
+			LineSource::Axiom5(p, q, l) => (vec![p, q], vec![l]),
+			LineSource::Axiom6(p, q, l, m) => (vec![p, q], vec![l, m]),
+			LineSource::Axiom7(p, l, m) => (vec![p], vec![l, m])
+		};
+		for p in points { self.collect_point(p, steps); }
+		for l in lines { self.collect_line(l, steps); }
+		steps.push(index);
+	}
+	// the best ways to make a point, the most accurate first, and for
+	// ones that are equally accurate, the fewest folds first
+	pub fn find_point (&self, target: Vector, count: usize) -> Vec<Sequence> {
+		let sequences = self.points.iter().enumerate()
+			.map(|(i, p)| {
+				let steps = self.point_steps(i);
+				Sequence { index: i, error: p.point.distance_to(target), folds: steps.len(), steps }
+			})
+			.collect();
+		return best(sequences, count);
+	}
+	// the error of a line is the farthest the target line (on the paper)
+	// is from it
+	pub fn find_line<B: Boundary> (&self, target: Line, boundary: B, count: usize) -> Vec<Sequence> {
+		let pieces = boundary.clip_all(target);
+		let ends: Vec<Vector> = pieces.iter().flat_map(|s| vec![s.a, s.b]).collect();
+		if ends.is_empty() { return vec![]; }
+		let sequences = self.lines.iter().enumerate()
+			.map(|(i, l)| {
+				let error = ends.iter()
+					.map(|p| (p.dot(l.line.u) - l.line.d).abs())
+					.fold(0.0, f64::max);
+				let steps = self.line_steps(i);
+				Sequence { index: i, error, folds: steps.len(), steps }
+			})
+			.collect();
+		return best(sequences, count);
+	}
+}
+
+impl Default for Database {
+	fn default () -> Database { Database::new() }
+}
+
+// the smallest errors first. errors are compared in steps of EPSILON, so
+// that of two nearly as good sequences the one with fewer folds comes first.
+// the steps keep it a total order (NaN is last), which sort_by needs.
+fn best (mut sequences: Vec<Sequence>, count: usize) -> Vec<Sequence> {
+	let step = |error: f64| (error / EPSILON).round();
+	sequences.sort_by(|a, b| step(a.error).total_cmp(&step(b.error))
+		.then(a.folds.cmp(&b.folds))
+		.then(a.error.total_cmp(&b.error)));
+	sequences.truncate(count);
+	return sequences;
+}
@@ -0,0 +1,260 @@
+// a reference database saved to disk, so a deep database only has to be
+// built once for each paper. the file is little endian, a header followed
+// by fixed size records, so it can be read in one pass.
+//   header: magic (8) version (4) depth (4) paper (8) points (4) lines (4)
+//   point:  x y (8 each) rank (4) source (4) line line (4 each)
+//   line:   u.x u.y d (8 each) rank (4) source (4) parents (4 x 4)
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use math::Vector;
+use math::Line;
+use math::Boundary;
+use super::Database;
+use super::LineSource;
+use super::PointSource;
+use super::quantize;
+
+const MAGIC: &[u8; 8] = b"EARREFDB";
+const VERSION: u32 = 1;
+const HEADER: usize = 32;
+const POINT: usize = 32;
+const LINE: usize = 48;
+
+#[derive(Debug)]
+pub enum CacheError {
+	Io(io::Error),
+	// the file doesn't start with the magic bytes
+	NotACache,
+	// the file was written by a different version of the format
+	Version(u32),
+	// the file is cut short or refers to something that isn't there
+	Corrupt(String)
+}
+
+impl fmt::Display for CacheError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			CacheError::Io(ref e) => write!(f, "{}", e),
+			CacheError::NotACache => write!(f, "not a reference database file"),
+			CacheError::Version(v) => write!(f, "unsupported reference database version {}", v),
+			CacheError::Corrupt(ref reason) => write!(f, "corrupt reference database: {}", reason)
+		}
+	}
+}
+
+impl ::std::error::Error for CacheError {}
+
+impl From<io::Error> for CacheError {
+	fn from (e: io::Error) -> CacheError { CacheError::Io(e) }
+}
+
+// a hash of the paper's corners (FNV-1a), the same paper always gives the
+// same number, in every build. corners are rounded like the database does.
+pub fn paper_key<B: Boundary> (boundary: B) -> u64 {
+	let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+	let mut eat = |n: i64| {
+		for byte in n.to_le_bytes().iter() {
+			hash ^= u64::from(*byte);
+			hash = hash.wrapping_mul(0x0100_0000_01b3);
+		}
+	};
+	for ring in boundary.rings() {
+		eat(ring.len() as i64);
+		for p in ring.iter() {
+			eat(quantize(p.x));
+			eat(quantize(p.y));
+		}
+	}
+	return hash;
+}
+
+// where the database for this paper and depth lives inside a directory
+pub fn cache_path<B: Boundary, P: AsRef<Path>> (directory: P, boundary: B, depth: usize) -> PathBuf {
+	directory.as_ref().join(format!("reference-{:016x}-{}.bin", paper_key(boundary), depth))
+}
+
+fn line_source (source: LineSource) -> (u32, [usize; 4]) {
+	match source {
+		LineSource::Edge => (0, [0, 0, 0, 0]),
+		LineSource::Axiom1(a, b) => (1, [a, b, 0, 0]),
+		LineSource::Axiom2(a, b) => (2, [a, b, 0, 0]),
+		LineSource::Axiom3(a, b) => (3, [a, b, 0, 0]),
+		LineSource::Axiom4(p, l) => (4, [p, l, 0, 0]),
+		LineSource::Axiom5(p, q, l) => (5, [p, q, l, 0]),
+		LineSource::Axiom6(p, q, l, m) => (6, [p, q, l, m]),
+		LineSource::Axiom7(p, l, m) => (7, [p, l, m, 0])
+	}
+}
+
+// the source of a line, if its parents exist. (points, lines) are the counts.
+fn read_line_source (tag: u32, n: [usize; 4], points: usize, lines: usize) -> Option<LineSource> {
+	let p = |i: usize| n[i] < points;
+	let l = |i: usize| n[i] < lines;
+	let source = match tag {
+		0 => LineSource::Edge,
+		1 if p(0) && p(1) => LineSource::Axiom1(n[0], n[1]),
+		2 if p(0) && p(1) => LineSource::Axiom2(n[0], n[1]),
+		3 if l(0) && l(1) => LineSource::Axiom3(n[0], n[1]),
+		4 if p(0) && l(1) => LineSource::Axiom4(n[0], n[1]),
+		5 if p(0) && p(1) && l(2) => LineSource::Axiom5(n[0], n[1], n[2]),
+		6 if p(0) && p(1) && l(2) && l(3) => LineSource::Axiom6(n[0], n[1], n[2], n[3]),
+		7 if p(0) && l(1) && l(2) => LineSource::Axiom7(n[0], n[1], n[2]),
+		_ => return None
+	};
+	return Some(source);
+}
+
+fn u32_at (bytes: &[u8], i: usize) -> u32 {
+	let mut b = [0; 4];
+	b.copy_from_slice(&bytes[i..i + 4]);
+	u32::from_le_bytes(b)
+}
+
+fn u64_at (bytes: &[u8], i: usize) -> u64 {
+	let mut b = [0; 8];
+	b.copy_from_slice(&bytes[i..i + 8]);
+	u64::from_le_bytes(b)
+}
+
+fn f64_at (bytes: &[u8], i: usize) -> f64 { f64::from_bits(u64_at(bytes, i)) }
+
+impl Database {
+	pub fn to_bytes (&self, paper: u64) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(HEADER + self.points.len() * POINT + self.lines.len() * LINE);
+		bytes.extend_from_slice(MAGIC);
+		bytes.extend_from_slice(&VERSION.to_le_bytes());
+		bytes.extend_from_slice(&(self.depth as u32).to_le_bytes());
+		bytes.extend_from_slice(&paper.to_le_bytes());
+		bytes.extend_from_slice(&(self.points.len() as u32).to_le_bytes());
+		bytes.extend_from_slice(&(self.lines.len() as u32).to_le_bytes());
+		for p in self.points.iter() {
+			let (tag, a, b) = match p.source {
+				PointSource::Corner => (0u32, 0, 0),
+				PointSource::Intersection(a, b) => (1, a, b)
+			};
+			bytes.extend_from_slice(&p.point.x.to_le_bytes());
+			bytes.extend_from_slice(&p.point.y.to_le_bytes());
+			for n in [p.rank as u32, tag, a as u32, b as u32].iter() {
+				bytes.extend_from_slice(&n.to_le_bytes());
+			}
+		}
+		for l in self.lines.iter() {
+			let (tag, parents) = line_source(l.source);
+			for n in [l.line.u.x, l.line.u.y, l.line.d].iter() {
+				bytes.extend_from_slice(&n.to_le_bytes());
+			}
+			bytes.extend_from_slice(&(l.rank as u32).to_le_bytes());
+			bytes.extend_from_slice(&tag.to_le_bytes());
+			for n in parents.iter() {
+				bytes.extend_from_slice(&(*n as u32).to_le_bytes());
+			}
+		}
+		return bytes;
+	}
+	// the database and the paper key it was saved with
+	pub fn from_bytes (bytes: &[u8]) -> Result<(Database, u64), CacheError> {
+		if bytes.len() < HEADER || &bytes[0..8] != MAGIC { return Err(CacheError::NotACache); }
+		let version = u32_at(bytes, 8);
+		if version != VERSION { return Err(CacheError::Version(version)); }
+		let depth = u32_at(bytes, 12) as usize;
+		let paper = u64_at(bytes, 16);
+		let points = u32_at(bytes, 24) as usize;
+		let lines = u32_at(bytes, 28) as usize;
+		let length = HEADER + points * POINT + lines * LINE;
+		if bytes.len() != length {
+			return Err(CacheError::Corrupt(format!("expected {} bytes, found {}", length, bytes.len())));
+		}
+		let mut database = Database::new();
+		database.depth = depth;
+		for i in 0..points {
+			let at = HEADER + i * POINT;
+			let point = Vector { x: f64_at(bytes, at), y: f64_at(bytes, at + 8) };
+			let rank = u32_at(bytes, at + 16) as usize;
+			let (a, b) = (u32_at(bytes, at + 24) as usize, u32_at(bytes, at + 28) as usize);
+			let source = match u32_at(bytes, at + 20) {
+				0 => PointSource::Corner,
+				1 if a < lines && b < lines => PointSource::Intersection(a, b),
+				_ => return Err(CacheError::Corrupt(format!("point {} has an invalid source", i)))
+			};
+			if !database.add_point(point, source, rank) {
+				return Err(CacheError::Corrupt(format!("point {} is a duplicate", i)));
+			}
+		}
+		for i in 0..lines {
+			let at = HEADER + points * POINT + i * LINE;
+			let line = Line { u: Vector { x: f64_at(bytes, at), y: f64_at(bytes, at + 8) }, d: f64_at(bytes, at + 16) };
+			let rank = u32_at(bytes, at + 24) as usize;
+			let tag = u32_at(bytes, at + 28);
+			let mut parents = [0; 4];
+			for (j, n) in parents.iter_mut().enumerate() { *n = u32_at(bytes, at + 32 + j * 4) as usize; }
+			let source = match read_line_source(tag, parents, points, lines) {
+				Some(source) => source,
+				None => return Err(CacheError::Corrupt(format!("line {} has an invalid source", i)))
+			};
+			if !database.add_line(line, source, rank) {
+				return Err(CacheError::Corrupt(format!("line {} is a duplicate", i)));
+			}
+		}
+		// every element is made from elements with fewer folds, otherwise
+		// a broken file could send the steps around in a circle
+		for (i, p) in database.points.iter().enumerate() {
+			if let PointSource::Intersection(a, b) = p.source {
+				if database.lines[a].rank + database.lines[b].rank != p.rank {
+					return Err(CacheError::Corrupt(format!("point {} has the wrong rank", i)));
+				}
+			}
+		}
+		for (i, l) in database.lines.iter().enumerate() {
+			let parents = database.parent_ranks(l.source);
+			if l.source != LineSource::Edge && parents + 1 != l.rank {
+				return Err(CacheError::Corrupt(format!("line {} has the wrong rank", i)));
+			}
+		}
+		return Ok((database, paper));
+	}
+	// the folds it took to make the parents of a line
+	fn parent_ranks (&self, source: LineSource) -> usize {
+		let (tag, n) = line_source(source);
+		let p = |i: usize| self.points[n[i]].rank;
+		let l = |i: usize| self.lines[n[i]].rank;
+		match tag {
+			1 | 2 => p(0) + p(1),
+			3 => l(0) + l(1),
+			4 => p(0) + l(1),
+			5 => p(0) + p(1) + l(2),
+			6 => p(0) + p(1) + l(2) + l(3),
+			7 => p(0) + l(1) + l(2),
+			_ => 0
+		}
+	}
+	// written next to the destination and then renamed, so that a reader
+	// never finds half of a file
+	pub fn write<P: AsRef<Path>> (&self, path: P, paper: u64) -> Result<(), CacheError> {
+		let path = path.as_ref();
+		let mut partial = path.as_os_str().to_owned();
+		partial.push(".partial");
+		fs::write(&partial, self.to_bytes(paper))?;
+		fs::rename(&partial, path)?;
+		return Ok(());
+	}
+	pub fn read<P: AsRef<Path>> (path: P) -> Result<(Database, u64), CacheError> {
+		return Database::from_bytes(&fs::read(path)?);
+	}
+	// the database for this paper and depth, read from the directory if it
+	// was made before, otherwise built and saved there for next time.
+	// a missing or unreadable file is rebuilt, only failing to save is an error.
+	pub fn cached<B: Boundary, P: AsRef<Path>> (boundary: B, depth: usize, directory: P) -> Result<Database, CacheError> {
+		let paper = paper_key(&boundary);
+		let path = cache_path(directory.as_ref(), &boundary, depth);
+		if let Ok((database, key)) = Database::read(&path) {
+			if key == paper && database.depth == depth { return Ok(database); }
+		}
+		let database = Database::build(&boundary, depth);
+		fs::create_dir_all(directory)?;
+		database.write(&path, paper)?;
+		return Ok(database);
+	}
+}
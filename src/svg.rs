@@ -0,0 +1,210 @@
+// drawing the paper, fold lines, points and crease patterns as SVG,
+// and reading crease patterns back out of SVG drawings (see read.rs).
+// the drawing is collected first and written at the end, so that the
+// viewBox can be fit around everything that was drawn.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use math::Vector;
+use math::Line;
+use math::Segment;
+use math::Boundary;
+use math::EPSILON;
+use graph::Graph;
+use graph::Assignment;
+
+mod read;
+pub use self::read::parse;
+pub use self::read::parse_segments;
+pub use self::read::AssignmentTable;
+pub use self::read::Rule;
+pub use self::read::DashStyle;
+pub use self::read::SvgError;
+
+// stroke widths are in screen pixels, they don't scale with the viewBox
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+	pub stroke: String,
+	pub stroke_width: f64,
+	pub dasharray: Option<String>,
+	pub fill: String
+}
+
+impl Style {
+	pub fn new (stroke: &str, stroke_width: f64, dasharray: Option<&str>) -> Style {
+		Style {
+			stroke: stroke.to_string(),
+			stroke_width,
+			dasharray: dasharray.map(|d| d.to_string()),
+			fill: "none".to_string()
+		}
+	}
+	fn attributes (&self) -> String {
+		let mut attributes = format!(
+			"stroke=\"{}\" stroke-width=\"{}\" fill=\"{}\" vector-effect=\"non-scaling-stroke\"",
+			self.stroke, self.stroke_width, self.fill);
+		if let Some(ref dasharray) = self.dasharray {
+			attributes += &format!(" stroke-dasharray=\"{}\"", dasharray);
+		}
+		return attributes;
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Styles {
+	pub boundary: Style,
+	pub mountain: Style,
+	pub valley: Style,
+	pub flat: Style,
+	pub unassigned: Style,
+	pub cut: Style,
+	// lines drawn with Svg::line, like the results of the axioms
+	pub fold_line: Style,
+	pub point: Style,
+	// the radius of a point, as a fraction of the larger side of the viewBox
+	pub point_radius: f64
+}
+
+impl Styles {
+	pub fn assignment (&self, assignment: Assignment) -> &Style {
+		match assignment {
+			Assignment::Boundary => &self.boundary,
+			Assignment::Mountain => &self.mountain,
+			Assignment::Valley => &self.valley,
+			Assignment::Flat => &self.flat,
+			Assignment::Unassigned => &self.unassigned,
+			Assignment::Cut => &self.cut
+		}
+	}
+}
+
+impl Default for Styles {
+	fn default () -> Styles {
+		let mut point = Style::new("none", 0.0, None);
+		point.fill = "black".to_string();
+		Styles {
+			boundary: Style::new("black", 2.0, None),
+			mountain: Style::new("#e53", 1.5, Some("8 3 2 3")),
+			valley: Style::new("#38c", 1.5, Some("6 4")),
+			flat: Style::new("#bbb", 1.0, None),
+			unassigned: Style::new("#888", 1.0, Some("2 2")),
+			cut: Style::new("#9c3", 2.0, None),
+			fold_line: Style::new("#e53", 1.5, Some("6 4")),
+			point,
+			point_radius: 0.01
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+enum Element {
+	Polygon(Vec<Vec<Vector>>, Style),
+	Segment(Segment, Style),
+	Point(Vector, Style)
+}
+
+#[derive(Debug, Clone)]
+pub struct Svg {
+	pub styles: Styles,
+	// x, y, width, height. if None, fit the viewBox around the drawing
+	pub view_box: Option<[f64; 4]>,
+	// the empty space around the drawing when the viewBox is fit to it,
+	// as a fraction of the larger side
+	pub padding: f64,
+	elements: Vec<Element>
+}
+
+impl Default for Svg {
+	fn default () -> Svg {
+		Svg { styles: Styles::default(), view_box: None, padding: 0.05, elements: vec![] }
+	}
+}
+
+impl Svg {
+	pub fn new () -> Svg { Svg::default() }
+	pub fn with_styles (styles: Styles) -> Svg {
+		Svg { styles, ..Svg::new() }
+	}
+	// the outline of the paper, including any holes
+	pub fn boundary<B: Boundary> (&mut self, boundary: B) {
+		let style = self.styles.boundary.clone();
+		self.elements.push(Element::Polygon(boundary.rings(), style));
+	}
+	// a fold line, clipped to the paper
+	pub fn line<B: Boundary> (&mut self, line: Line, boundary: B) {
+		for segment in boundary.clip_all(line) {
+			let style = self.styles.fold_line.clone();
+			self.elements.push(Element::Segment(segment, style));
+		}
+	}
+	pub fn lines<B: Boundary> (&mut self, lines: &[Line], boundary: B) {
+		for line in lines { self.line(*line, &boundary); }
+	}
+	pub fn segment (&mut self, segment: Segment, style: &Style) {
+		self.elements.push(Element::Segment(segment, style.clone()));
+	}
+	pub fn point (&mut self, point: Vector) {
+		let style = self.styles.point.clone();
+		self.elements.push(Element::Point(point, style));
+	}
+	// every edge of the crease pattern, styled by its assignment
+	pub fn graph (&mut self, graph: &Graph) {
+		for (e, assignment) in graph.edges_assignment.iter().enumerate() {
+			let style = self.styles.assignment(*assignment).clone();
+			self.elements.push(Element::Segment(graph.edge_segment(e), style));
+		}
+	}
+	fn points (&self) -> Vec<Vector> {
+		self.elements.iter()
+			.flat_map(|el| match *el {
+				Element::Polygon(ref rings, _) => rings.concat(),
+				Element::Segment(s, _) => vec![s.a, s.b],
+				Element::Point(p, _) => vec![p]
+			})
+			.collect()
+	}
+	fn fit_view_box (&self) -> [f64; 4] {
+		let points = self.points();
+		if points.is_empty() { return [0.0, 0.0, 1.0, 1.0]; }
+		let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+		let min_y = points.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+		let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+		let max_y = points.iter().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max);
+		let size = (max_x - min_x).max(max_y - min_y).max(EPSILON);
+		let pad = size * self.padding;
+		return [min_x - pad, min_y - pad, max_x - min_x + 2.0 * pad, max_y - min_y + 2.0 * pad];
+	}
+	pub fn write<P: AsRef<Path>> (&self, path: P) -> io::Result<()> {
+		fs::write(path, self.to_string())
+	}
+}
+
+impl fmt::Display for Svg {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let view_box = self.view_box.unwrap_or_else(|| self.fit_view_box());
+		let radius = view_box[2].max(view_box[3]) * self.styles.point_radius;
+		writeln!(f, "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+			view_box[0], view_box[1], view_box[2], view_box[3])?;
+		for element in self.elements.iter() {
+			match *element {
+				Element::Polygon(ref rings, ref style) => {
+					let d: Vec<String> = rings.iter()
+						.map(|ring| ring.iter().enumerate()
+							.map(|(i, p)| format!("{}{} {}", if i == 0 { "M" } else { "L" }, p.x, p.y))
+							.collect::<Vec<String>>()
+							.join(" ") + " Z")
+						.collect();
+					writeln!(f, "<path d=\"{}\" fill-rule=\"evenodd\" {}/>", d.join(" "), style.attributes())?;
+				},
+				Element::Segment(s, ref style) =>
+					writeln!(f, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {}/>",
+						s.a.x, s.a.y, s.b.x, s.b.y, style.attributes())?,
+				Element::Point(p, ref style) =>
+					writeln!(f, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" {}/>",
+						p.x, p.y, radius, style.attributes())?
+			}
+		}
+		write!(f, "</svg>")
+	}
+}
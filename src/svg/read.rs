@@ -0,0 +1,353 @@
+// reading a crease pattern out of an SVG drawing. the line, polyline,
+// polygon, path and rect elements become segments, the assignment of each
+// is decided by its stroke color and dash style, and all of the segments
+// are joined into one planar graph.
+use std::fmt;
+use roxmltree;
+use math::Vector;
+use math::Segment;
+use math::Matrix;
+use graph::Graph;
+use graph::Assignment;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgError {
+	// the text is not valid XML
+	Xml(String),
+	// (element, attribute, value) an attribute couldn't be understood
+	InvalidAttribute(String, String, String)
+}
+
+impl fmt::Display for SvgError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			SvgError::Xml(ref e) => write!(f, "invalid XML: {}", e),
+			SvgError::InvalidAttribute(ref element, ref attribute, ref value) =>
+				write!(f, "<{}> has an invalid {}: \"{}\"", element, attribute, value)
+		}
+	}
+}
+
+impl ::std::error::Error for SvgError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DashStyle {
+	Solid,
+	// a dasharray with 2 values (or a repeat of them)
+	Dashed,
+	// a dasharray with more than 2 distinct values, like "8 3 2 3"
+	DashDot
+}
+
+// a rule matches a stroke if every part that is Some matches.
+// the color is a lowercase "#rrggbb" string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+	pub color: Option<String>,
+	pub dash: Option<DashStyle>,
+	pub assignment: Assignment
+}
+
+// the rules are tested in order and the first match wins. a stroke
+// that matches no rule is unassigned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssignmentTable {
+	pub rules: Vec<Rule>
+}
+
+impl AssignmentTable {
+	pub fn assignment (&self, color: &str, dash: DashStyle) -> Assignment {
+		self.rules.iter()
+			.find(|rule| rule.color.as_ref().is_none_or(|c| c == color)
+				&& rule.dash.is_none_or(|d| d == dash))
+			.map_or(Assignment::Unassigned, |rule| rule.assignment)
+	}
+}
+
+impl Default for AssignmentTable {
+	// red is mountain, blue is valley, gray is flat, green is cut. a black
+	// line is a boundary if it's solid, otherwise the dashes decide.
+	fn default () -> AssignmentTable {
+		let rule = |color: Option<&str>, dash: Option<DashStyle>, assignment: Assignment| Rule {
+			color: color.map(|c| c.to_string()), dash, assignment
+		};
+		AssignmentTable { rules: vec![
+			rule(Some("#ff0000"), None, Assignment::Mountain),
+			rule(Some("#0000ff"), None, Assignment::Valley),
+			rule(Some("#808080"), None, Assignment::Flat),
+			rule(Some("#00ff00"), None, Assignment::Cut),
+			rule(Some("#008000"), None, Assignment::Cut),
+			rule(Some("#000000"), Some(DashStyle::Solid), Assignment::Boundary),
+			rule(None, Some(DashStyle::DashDot), Assignment::Mountain),
+			rule(None, Some(DashStyle::Dashed), Assignment::Valley)
+		]}
+	}
+}
+
+// split a list of numbers separated by commas and/or whitespace
+fn parse_numbers (text: &str) -> Option<Vec<f64>> {
+	text.split(|c: char| c == ',' || c.is_whitespace())
+		.filter(|s| !s.is_empty())
+		.map(|s| s.parse::<f64>().ok())
+		.collect()
+}
+
+fn parse_transform (text: &str) -> Option<Matrix> {
+	let mut matrix = Matrix::IDENTITY;
+	for part in text.split(')').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+		let mut pieces = part.splitn(2, '(');
+		let name = pieces.next()?.trim_matches(|c: char| c == ',' || c.is_whitespace());
+		let v = parse_numbers(pieces.next()?)?;
+		let next: Matrix = match (name, v.len()) {
+			("matrix", 6) => Matrix([v[0], v[1], v[2], v[3], v[4], v[5]]),
+			("translate", 1) => Matrix([1.0, 0.0, 0.0, 1.0, v[0], 0.0]),
+			("translate", 2) => Matrix([1.0, 0.0, 0.0, 1.0, v[0], v[1]]),
+			("scale", 1) => Matrix([v[0], 0.0, 0.0, v[0], 0.0, 0.0]),
+			("scale", 2) => Matrix([v[0], 0.0, 0.0, v[1], 0.0, 0.0]),
+			("rotate", 1) | ("rotate", 3) => {
+				let (sin, cos) = v[0].to_radians().sin_cos();
+				let rotate = Matrix([cos, sin, -sin, cos, 0.0, 0.0]);
+				if v.len() == 1 { rotate } else {
+					let there = Matrix([1.0, 0.0, 0.0, 1.0, v[1], v[2]]);
+					let back = Matrix([1.0, 0.0, 0.0, 1.0, -v[1], -v[2]]);
+					there.multiply(rotate).multiply(back)
+				}
+			},
+			("skewX", 1) => Matrix([1.0, 0.0, v[0].to_radians().tan(), 1.0, 0.0, 0.0]),
+			("skewY", 1) => Matrix([1.0, v[0].to_radians().tan(), 0.0, 1.0, 0.0, 0.0]),
+			_ => return None
+		};
+		matrix = matrix.multiply(next);
+	}
+	return Some(matrix);
+}
+
+// colors become lowercase "#rrggbb". only the basic named colors are known
+fn parse_color (text: &str) -> String {
+	let text = text.trim().to_lowercase();
+	let named = match text.as_str() {
+		"black" => Some("#000000"),
+		"white" => Some("#ffffff"),
+		"red" => Some("#ff0000"),
+		"lime" => Some("#00ff00"),
+		"green" => Some("#008000"),
+		"blue" => Some("#0000ff"),
+		"gray" | "grey" => Some("#808080"),
+		"yellow" => Some("#ffff00"),
+		"orange" => Some("#ffa500"),
+		"purple" => Some("#800080"),
+		"magenta" | "fuchsia" => Some("#ff00ff"),
+		"cyan" | "aqua" => Some("#00ffff"),
+		_ => None
+	};
+	if let Some(color) = named { return color.to_string(); }
+	if text.starts_with('#') && text.len() == 4 {
+		return text.chars().skip(1)
+			.fold("#".to_string(), |s, c| format!("{}{}{}", s, c, c));
+	}
+	if text.starts_with("rgb(") && text.ends_with(')') {
+		if let Some(v) = parse_numbers(&text[4..text.len() - 1]) {
+			if v.len() == 3 {
+				let channel = |n: f64| n.clamp(0.0, 255.0).round() as u8;
+				return format!("#{:02x}{:02x}{:02x}", channel(v[0]), channel(v[1]), channel(v[2]));
+			}
+		}
+	}
+	return text;
+}
+
+fn parse_dash (text: &str) -> DashStyle {
+	let values = parse_numbers(text).unwrap_or_default();
+	if values.is_empty() || values.iter().all(|v| *v == 0.0) { return DashStyle::Solid; }
+	let mut distinct: Vec<f64> = vec![];
+	for v in values { if !distinct.contains(&v) { distinct.push(v); } }
+	return if distinct.len() > 2 { DashStyle::DashDot } else { DashStyle::Dashed };
+}
+
+// a property can be set in the style attribute (which wins)
+// or as its own attribute, and is inherited from the parent elements
+fn property (node: roxmltree::Node, name: &str) -> Option<String> {
+	for ancestor in node.ancestors().filter(|n| n.is_element()) {
+		let style = ancestor.attribute("style").and_then(|style| style.split(';')
+			.map(|declaration| declaration.splitn(2, ':').collect::<Vec<&str>>())
+			.filter(|pair| pair.len() == 2 && pair[0].trim() == name)
+			.map(|pair| pair[1].trim().to_string())
+			.next_back());
+		if style.is_some() { return style; }
+		if let Some(value) = ancestor.attribute(name) { return Some(value.to_string()); }
+	}
+	return None;
+}
+
+fn number_attribute (node: roxmltree::Node, name: &str) -> Result<f64, SvgError> {
+	match node.attribute(name) {
+		None => Ok(0.0),
+		Some(value) => value.trim().trim_end_matches("px").parse::<f64>()
+			.map_err(|_| invalid(node, name, value))
+	}
+}
+
+fn invalid (node: roxmltree::Node, name: &str, value: &str) -> SvgError {
+	SvgError::InvalidAttribute(
+		node.tag_name().name().to_string(), name.to_string(), value.to_string())
+}
+
+enum Token {
+	Command(char),
+	Number(f64)
+}
+
+// the straight pieces of a path. curves are not supported, the pen moves
+// to the end of the curve but the curve itself is skipped.
+fn parse_path (d: &str) -> Option<Vec<Segment>> {
+	// split the path into command letters and numbers
+	let mut tokens: Vec<Token> = vec![];
+	let chars: Vec<char> = d.chars().collect();
+	let mut i = 0;
+	while i < chars.len() {
+		let c = chars[i];
+		if c.is_ascii_alphabetic() && c != 'e' && c != 'E' {
+			tokens.push(Token::Command(c));
+			i += 1;
+		} else if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
+			let start = i;
+			let mut seen_dot = c == '.';
+			i += 1;
+			while i < chars.len() {
+				let n = chars[i];
+				if n.is_ascii_digit() { i += 1; }
+				else if n == '.' && !seen_dot { seen_dot = true; i += 1; }
+				else if (n == 'e' || n == 'E') && i + 1 < chars.len() {
+					i += 1;
+					if chars[i] == '-' || chars[i] == '+' { i += 1; }
+					while i < chars.len() && chars[i].is_ascii_digit() { i += 1; }
+					break;
+				}
+				else { break; }
+			}
+			let number: String = chars[start..i].iter().collect();
+			tokens.push(Token::Number(number.parse::<f64>().ok()?));
+		} else {
+			i += 1;
+		}
+	}
+	let mut segments = vec![];
+	let mut pen = Vector { x: 0.0, y: 0.0 };
+	let mut start = pen;
+	let mut command = ' ';
+	let mut t = 0;
+	while t < tokens.len() {
+		if let Token::Command(c) = tokens[t] { command = c; t += 1; }
+		let relative = command.is_ascii_lowercase();
+		let offset = if relative { pen } else { Vector { x: 0.0, y: 0.0 } };
+		let count = match command.to_ascii_uppercase() {
+			'Z' => 0,
+			'H' | 'V' => 1,
+			'M' | 'L' | 'T' => 2,
+			'S' | 'Q' => 4,
+			'C' => 6,
+			'A' => 7,
+			_ => return None
+		};
+		let mut v: Vec<f64> = vec![];
+		for _ in 0..count {
+			match tokens.get(t) {
+				Some(Token::Number(n)) => { v.push(*n); t += 1; },
+				_ => return None
+			}
+		}
+		let next = match command.to_ascii_uppercase() {
+			'Z' => start,
+			'H' => Vector { x: v[0] + offset.x, y: pen.y },
+			'V' => Vector { x: pen.x, y: v[0] + offset.y },
+			_ => Vector { x: v[count - 2] + offset.x, y: v[count - 1] + offset.y }
+		};
+		match command.to_ascii_uppercase() {
+			'M' => {
+				start = next;
+				// numbers following a moveto are linetos
+				command = if relative { 'l' } else { 'L' };
+			},
+			'L' | 'H' | 'V' | 'Z' => segments.push(Segment { a: pen, b: next }),
+			_ => ()
+		}
+		pen = next;
+		// a Z with no following command would loop forever
+		if count == 0 && !matches!(tokens.get(t), Some(Token::Command(_))) { break; }
+	}
+	return Some(segments);
+}
+
+// the segments that a single element draws, in its own coordinates
+fn element_segments (node: roxmltree::Node) -> Result<Vec<Segment>, SvgError> {
+	let v = |x: f64, y: f64| Vector { x, y };
+	let connect = |points: &[Vector], closed: bool| {
+		let mut segments: Vec<Segment> = points.windows(2)
+			.map(|pair| Segment { a: pair[0], b: pair[1] })
+			.collect();
+		if closed && points.len() > 2 {
+			segments.push(Segment { a: points[points.len() - 1], b: points[0] });
+		}
+		segments
+	};
+	let name = node.tag_name().name();
+	match name {
+		"line" => Ok(vec![Segment {
+			a: v(number_attribute(node, "x1")?, number_attribute(node, "y1")?),
+			b: v(number_attribute(node, "x2")?, number_attribute(node, "y2")?)
+		}]),
+		"rect" => {
+			let (x, y) = (number_attribute(node, "x")?, number_attribute(node, "y")?);
+			let (w, h) = (number_attribute(node, "width")?, number_attribute(node, "height")?);
+			Ok(connect(&[v(x, y), v(x + w, y), v(x + w, y + h), v(x, y + h)], true))
+		},
+		"polyline" | "polygon" => {
+			let text = node.attribute("points").unwrap_or("");
+			let numbers = parse_numbers(text)
+				.filter(|n| n.len() % 2 == 0)
+				.ok_or_else(|| invalid(node, "points", text))?;
+			let points: Vec<Vector> = numbers.chunks(2).map(|p| v(p[0], p[1])).collect();
+			Ok(connect(&points, name == "polygon"))
+		},
+		"path" => {
+			let d = node.attribute("d").unwrap_or("");
+			parse_path(d).ok_or_else(|| invalid(node, "d", d))
+		},
+		_ => Ok(vec![])
+	}
+}
+
+// every straight segment in the drawing with the assignment from its stroke.
+// the transforms of the element and all of its parents are applied.
+// elements without a stroke color are treated as black.
+pub fn parse_segments (text: &str, table: &AssignmentTable) -> Result<Vec<(Segment, Assignment)>, SvgError> {
+	let document = roxmltree::Document::parse(text).map_err(|e| SvgError::Xml(e.to_string()))?;
+	let mut result = vec![];
+	for node in document.descendants().filter(|n| n.is_element()) {
+		// skip anything that is only a definition, not drawn
+		if node.ancestors().any(|a| a.tag_name().name() == "defs") { continue; }
+		let segments = element_segments(node)?;
+		if segments.is_empty() { continue; }
+		let stroke = property(node, "stroke").unwrap_or_else(|| "black".to_string());
+		if stroke.trim() == "none" { continue; }
+		let dash = parse_dash(&property(node, "stroke-dasharray").unwrap_or_default());
+		let assignment = table.assignment(&parse_color(&stroke), dash);
+		let mut matrix = Matrix::IDENTITY;
+		for ancestor in node.ancestors().filter(|n| n.is_element()) {
+			if let Some(transform) = ancestor.attribute("transform") {
+				let m = parse_transform(transform).ok_or_else(|| invalid(ancestor, "transform", transform))?;
+				matrix = m.multiply(matrix);
+			}
+		}
+		for s in segments {
+			let segment = matrix.transform_segment(s);
+			result.push((segment, assignment));
+		}
+	}
+	return Ok(result);
+}
+
+// a crease pattern from an SVG, where crossing lines split each other
+pub fn parse (text: &str, table: &AssignmentTable) -> Result<Graph, SvgError> {
+	return Ok(Graph::from_segments(&parse_segments(text, table)?));
+}
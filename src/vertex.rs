@@ -0,0 +1,285 @@
+// local flat-foldability, around one vertex at a time.
+// a vertex inside the paper folds flat only if the sector angles between
+// its creases alternate to sum to pi (Kawasaki) and the number of mountains
+// and valleys differ by two (Maekawa). boundary and cut edges make a vertex
+// part of the paper's edge, flat edges aren't folded and are ignored.
+// beyond checking, a vertex can be completed: the missing crease found,
+// and its unassigned creases given every valid mountain valley assignment.
+use std::f64::consts::PI;
+use math::Vector;
+use math::Line;
+use math::Segment;
+use math::Boundary;
+use math::EPSILON;
+use graph::Graph;
+use graph::Assignment;
+
+// the angles between each vector and the next, counter-clockwise.
+// the vectors must already be sorted counter-clockwise. the angles sum to 2 pi.
+pub fn sector_angles (vectors: &[Vector]) -> Vec<f64> {
+	(0..vectors.len())
+		.map(|i| vectors[i].angle_to(vectors[(i + 1) % vectors.len()]))
+		.map(|a| if a == 0.0 && vectors.len() == 1 { 2.0 * PI } else { a })
+		.collect()
+}
+
+// the alternating sum of the sector angles, minus pi. 0 when Kawasaki's
+// theorem holds. None if there is an odd number of sectors, which never folds flat.
+pub fn kawasaki_error (sectors: &[f64]) -> Option<f64> {
+	if sectors.is_empty() || sectors.len() % 2 == 1 { return None; }
+	let even: f64 = sectors.iter().step_by(2).sum();
+	return Some(even - PI);
+}
+
+// |M - V| - 2. 0 when Maekawa's theorem holds. None if any crease is unassigned.
+pub fn maekawa_error (assignments: &[Assignment]) -> Option<i32> {
+	if assignments.contains(&Assignment::Unassigned) { return None; }
+	let count = |x: Assignment| assignments.iter().filter(|a| **a == x).count() as i32;
+	return Some((count(Assignment::Mountain) - count(Assignment::Valley)).abs() - 2);
+}
+
+// the directions of one more crease which would satisfy Kawasaki's theorem,
+// for creases (sorted counter-clockwise) which are one short of an even count.
+// each sector can hold at most one solution: the new crease splits it so that
+// the alternating sum becomes pi.
+pub fn kawasaki_directions (vectors: &[Vector]) -> Vec<Vector> {
+	if vectors.len() % 2 != 1 { return vec![]; }
+	let sectors = sector_angles(vectors);
+	let mut directions = vec![];
+	for (k, sector) in sectors.iter().enumerate() {
+		// after the split, the sectors before k keep their place in the
+		// alternation, and the sectors after k shift over by one
+		let before: f64 = sectors.iter().take(k).step_by(2).sum();
+		let after: f64 = sectors.iter().enumerate().skip(k + 1)
+			.filter(|(i, _)| i % 2 == 1)
+			.map(|(_, a)| a)
+			.sum();
+		let x = if k % 2 == 0 { PI - before - after } else { sector - PI + before + after };
+		if x < EPSILON || x > sector - EPSILON { continue; }
+		let angle = vectors[k].angle() + x;
+		directions.push(Vector { x: angle.cos(), y: angle.sin() });
+	}
+	return directions;
+}
+
+// the missing crease as rays from the origin, clipped to the paper.
+// the vectors point from the origin along each crease, sorted counter-clockwise.
+pub fn kawasaki_solutions<B: Boundary> (origin: Vector, vectors: &[Vector], boundary: B) -> Vec<Segment> {
+	let mut solutions = vec![];
+	for direction in kawasaki_directions(vectors) {
+		let u = direction.rotate90();
+		let line = Line { u, d: origin.dot(u) };
+		// of the pieces of the line on the paper, the ray follows
+		// the one which contains the origin
+		for piece in boundary.clip_all(line) {
+			let (ta, tb) = (piece.a.subtract(origin).dot(direction), piece.b.subtract(origin).dot(direction));
+			if ta.min(tb) > EPSILON || ta.max(tb) < EPSILON { continue; }
+			let end = if ta > tb { piece.a } else { piece.b };
+			solutions.push(Segment { a: origin, b: end });
+		}
+	}
+	return solutions;
+}
+
+// a sector is a local minimum when its neighbors are at least as large
+fn is_local_minimum (sectors: &[f64], i: usize) -> bool {
+	let n = sectors.len();
+	sectors[i] <= sectors[(i + n - 1) % n] + EPSILON && sectors[i] <= sectors[(i + 1) % n] + EPSILON
+}
+
+// strictly smaller than both neighbors, the big-little-big lemma says
+// the two creases on either side must have opposite assignments
+fn is_strict_minimum (sectors: &[f64], i: usize) -> bool {
+	let n = sectors.len();
+	sectors[i] < sectors[(i + n - 1) % n] - EPSILON && sectors[i] < sectors[(i + 1) % n] - EPSILON
+}
+
+fn opposite (a: Assignment, b: Assignment) -> bool {
+	(a == Assignment::Mountain && b == Assignment::Valley)
+		|| (a == Assignment::Valley && b == Assignment::Mountain)
+}
+
+// does a vertex with these sector angles fold flat with these mountain and
+// valley creases? a local minimum sector between a mountain and a valley can be
+// crimped away (the two creases are removed and the three sectors become one)
+// without changing the answer. crimp until only two creases are left,
+// which fold flat if they are the same.
+pub fn is_valid_assignment (sectors: &[f64], assignments: &[Assignment]) -> bool {
+	if sectors.len() != assignments.len() { return false; }
+	if kawasaki_error(sectors).is_none_or(|e| e.abs() > EPSILON) { return false; }
+	let mut sectors = sectors.to_vec();
+	let mut assignments = assignments.to_vec();
+	while sectors.len() > 2 {
+		let n = sectors.len();
+		// sector i is between crease i and crease i + 1
+		let crimp = (0..n).find(|i| is_local_minimum(&sectors, *i)
+			&& opposite(assignments[*i], assignments[(*i + 1) % n]));
+		let i = match crimp {
+			Some(i) => i,
+			None => return false
+		};
+		// turn the circle so that the crimped sector is at index 1
+		let turn = (i + n - 1) % n;
+		sectors.rotate_left(turn);
+		assignments.rotate_left(turn);
+		sectors[0] = sectors[0] - sectors[1] + sectors[2];
+		sectors.drain(1..3);
+		assignments.drain(1..3);
+	}
+	return assignments.len() == 2
+		&& assignments[0] == assignments[1]
+		&& (assignments[0] == Assignment::Mountain || assignments[0] == Assignment::Valley);
+}
+
+// every valid assignment of the creases around a vertex. mountain and
+// valley creases keep their assignment, all others are tried both ways.
+// sector i is between crease i and crease i + 1.
+pub fn valid_assignments (sectors: &[f64], assignments: &[Assignment]) -> Vec<Vec<Assignment>> {
+	let mut solutions = vec![];
+	if sectors.len() != assignments.len() { return solutions; }
+	if kawasaki_error(sectors).is_none_or(|e| e.abs() > EPSILON) { return solutions; }
+	let mut current = vec![];
+	search_assignments(sectors, assignments, &mut current, &mut solutions);
+	return solutions;
+}
+
+pub fn count_assignments (sectors: &[f64], assignments: &[Assignment]) -> usize {
+	valid_assignments(sectors, assignments).len()
+}
+
+// assign the creases in order, dropping a branch as soon as the creases
+// around a strictly smallest sector break the big-little-big lemma
+fn search_assignments (
+	sectors: &[f64],
+	fixed: &[Assignment],
+	current: &mut Vec<Assignment>,
+	solutions: &mut Vec<Vec<Assignment>>
+) {
+	let n = sectors.len();
+	let j = current.len();
+	if j == n {
+		if is_strict_minimum(sectors, n - 1) && !opposite(current[n - 1], current[0]) { return; }
+		if is_valid_assignment(sectors, current) { solutions.push(current.clone()); }
+		return;
+	}
+	let options = match fixed[j] {
+		Assignment::Mountain => vec![Assignment::Mountain],
+		Assignment::Valley => vec![Assignment::Valley],
+		_ => vec![Assignment::Mountain, Assignment::Valley]
+	};
+	for option in options {
+		if j > 0 && is_strict_minimum(sectors, j - 1) && !opposite(current[j - 1], option) { continue; }
+		current.push(option);
+		search_assignments(sectors, fixed, current, solutions);
+		current.pop();
+	}
+}
+
+// the crease which is missing at a vertex of a crease pattern. a vertex
+// on the paper's edge has no solutions.
+pub fn missing_creases<B: Boundary> (graph: &Graph, vertex: usize, boundary: B) -> Vec<Segment> {
+	let creases = match creases_around(graph, vertex, &graph.vertices_edges()) {
+		Some(creases) => creases,
+		None => return vec![]
+	};
+	let vectors: Vec<Vector> = creases.iter().map(|e| crease_vector(graph, vertex, *e)).collect();
+	return kawasaki_solutions(graph.vertices_coords[vertex], &vectors, boundary);
+}
+
+// every valid mountain valley assignment at a vertex of a crease pattern,
+// keeping the creases which are already mountain or valley, as pairs of
+// (edge, assignment). a vertex on the paper's edge has no solutions.
+pub fn vertex_assignments (graph: &Graph, vertex: usize) -> Vec<Vec<(usize, Assignment)>> {
+	let creases = match creases_around(graph, vertex, &graph.vertices_edges()) {
+		Some(creases) => creases,
+		None => return vec![]
+	};
+	let vectors: Vec<Vector> = creases.iter().map(|e| crease_vector(graph, vertex, *e)).collect();
+	let assignments: Vec<Assignment> = creases.iter().map(|e| graph.edges_assignment[*e]).collect();
+	return valid_assignments(&sector_angles(&vectors), &assignments).into_iter()
+		.map(|solution| creases.iter().cloned().zip(solution).collect())
+		.collect();
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexReport {
+	pub vertex: usize,
+	// the folded edges around the vertex, counter-clockwise
+	pub creases: Vec<usize>,
+	// sector_angles[i] is between creases[i] and creases[i + 1]
+	pub sector_angles: Vec<f64>,
+	pub kawasaki: Option<f64>,
+	pub maekawa: Option<i32>
+}
+
+impl VertexReport {
+	pub fn is_kawasaki_valid (&self) -> bool {
+		self.kawasaki.is_some_and(|e| e.abs() < EPSILON)
+	}
+	// an unassigned vertex passes, its creases might still be assigned validly
+	pub fn is_maekawa_valid (&self) -> bool {
+		self.maekawa.is_none_or(|e| e == 0)
+	}
+}
+
+// every interior vertex with at least one crease
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+	pub vertices: Vec<VertexReport>
+}
+
+impl Report {
+	pub fn kawasaki_violations (&self) -> Vec<&VertexReport> {
+		self.vertices.iter().filter(|v| !v.is_kawasaki_valid()).collect()
+	}
+	pub fn maekawa_violations (&self) -> Vec<&VertexReport> {
+		self.vertices.iter().filter(|v| !v.is_maekawa_valid()).collect()
+	}
+	pub fn is_flat_foldable (&self) -> bool {
+		self.vertices.iter().all(|v| v.is_kawasaki_valid() && v.is_maekawa_valid())
+	}
+}
+
+// from the vertex along the edge, to the edge's other vertex
+fn crease_vector (graph: &Graph, vertex: usize, edge: usize) -> Vector {
+	let [a, b] = graph.edges_vertices[edge];
+	let other = if a == vertex { b } else { a };
+	return graph.vertices_coords[other].subtract(graph.vertices_coords[vertex]);
+}
+
+// the folded edges around a vertex sorted counter-clockwise, or None if
+// the vertex is on the paper's edge
+fn creases_around (graph: &Graph, vertex: usize, vertices_edges: &[Vec<usize>]) -> Option<Vec<usize>> {
+	let edges = &vertices_edges[vertex];
+	if edges.iter().any(|e| graph.edges_assignment[*e].is_boundary()) { return None; }
+	let mut creases: Vec<usize> = edges.iter()
+		.filter(|e| graph.edges_assignment[**e] != Assignment::Flat)
+		.cloned()
+		.collect();
+	let angle = |e: usize| crease_vector(graph, vertex, e).angle();
+	creases.sort_by(|a, b| angle(*a).partial_cmp(&angle(*b)).unwrap());
+	return Some(creases);
+}
+
+fn check_vertex (graph: &Graph, vertex: usize, creases: Vec<usize>) -> VertexReport {
+	let vectors: Vec<Vector> = creases.iter().map(|e| crease_vector(graph, vertex, *e)).collect();
+	let assignments: Vec<Assignment> = creases.iter().map(|e| graph.edges_assignment[*e]).collect();
+	let sectors = sector_angles(&vectors);
+	VertexReport {
+		vertex,
+		kawasaki: kawasaki_error(&sectors),
+		maekawa: maekawa_error(&assignments),
+		sector_angles: sectors,
+		creases
+	}
+}
+
+pub fn check (graph: &Graph) -> Report {
+	let vertices_edges = graph.vertices_edges();
+	let vertices = (0..graph.vertices_coords.len())
+		.filter_map(|v| creases_around(graph, v, &vertices_edges).map(|creases| (v, creases)))
+		.filter(|(_, creases)| !creases.is_empty())
+		.map(|(v, creases)| check_vertex(graph, v, creases))
+		.collect();
+	return Report { vertices };
+}
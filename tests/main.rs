@@ -1,13 +1,29 @@
+// the tests follow the same style as the library source
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+#![allow(clippy::bool_assert_comparison, clippy::suspicious_else_formatting)]
+
 extern crate rabbit_ear as ear;
 
 mod tests {
 
 	use ear::axioms;
+	use ear::fold::FoldFile;
+	use ear::fold::FoldError;
+	use ear::svg::Svg;
+	use ear::svg::AssignmentTable;
+	use ear::svg::SvgError;
 	use ear::Vector;
 	use ear::Line;
 	use ear::Segment;
 	use ear::Rect;
+	use ear::ConvexPolygon;
+	use ear::Polygon;
+	use ear::PolygonError;
+	use ear::Graph;
+	use ear::Assignment;
 	use ear::make_square;
+	use ear::make_rect;
+	use ear::make_regular_polygon;
 
 	const EPSILON: f64 = f64::EPSILON * 10.0;
 
@@ -104,7 +120,7 @@ mod tests {
 		let ax3b = axioms::axiom3(m, n, unit_square);
 		let _ax4 = axioms::axiom4(v, r, unit_square);
 		let _ax5 = axioms::axiom5(t, o, s, unit_square);
-		let _ax6 = axioms::axiom6(t, o, s, r, unit_square);
+		let _ax6 = axioms::axiom6(t, o, s, r, unit_square).unwrap();
 		let _ax7 = axioms::axiom7(o, r, l, unit_square);
 		// assert_eq!(ax3a.len(), 1);
 		// assert_delta!(ax3a[0].u.x, 0.7071067811865475, EPSILON);
@@ -128,6 +144,1217 @@ mod tests {
 		// println!("axiom 5 {:?}", ax5);
 		// println!("axiom 6 {:?}", ax6);
 		// println!("axiom 7 {:?}", ax7);
+		// every line that was found satisfies its axiom
+		use ear::axioms::{verify, Axiom};
+		let found = [
+			(Axiom::Axiom1(o, p), ax1),
+			(Axiom::Axiom2(o, p), ax2),
+			(Axiom::Axiom3(l, m), _ax3a),
+			(Axiom::Axiom3(m, n), ax3b),
+			(Axiom::Axiom4(v, r), _ax4),
+			(Axiom::Axiom5(t, o, s), _ax5),
+			(Axiom::Axiom6(t, o, s, r), _ax6),
+			(Axiom::Axiom7(o, r, l), _ax7)
+		];
+		for (axiom, lines) in found.iter() {
+			for line in lines.iter() { assert_delta!(verify(axiom, *line), 0.0, 1e-10); }
+		}
+	}
+
+	#[test]
+	fn polygon_tests () {
+		let a4: Rect = make_rect(1.0, 2.0_f64.sqrt());
+		assert_eq!(a4.contains(Vector { x: 0.5, y: 1.4 }), true);
+		assert_eq!(a4.contains(Vector { x: 0.5, y: 1.5 }), false);
+		assert_eq!(a4.contains(Vector { x: 1.0, y: 0.0 }), true);
+		let (success, segment) = a4.clip(Line { u: Vector { x: 1.0, y: 0.0 }, d: 0.5 });
+		assert_eq!(success, true);
+		assert_delta!(segment.a.distance_to(segment.b), 2.0_f64.sqrt(), EPSILON);
+
+		let hexagon: ConvexPolygon = make_regular_polygon(6, 1.0).unwrap();
+		assert_eq!(hexagon.sides().len(), 6);
+		assert_eq!(hexagon.contains(Vector { x: 0.0, y: 0.86 }), true);
+		assert_eq!(hexagon.contains(Vector { x: 0.0, y: 0.87 }), false);
+		let (success, segment) = hexagon.clip(Line { u: Vector { x: 0.0, y: 1.0 }, d: 0.0 });
+		assert_eq!(success, true);
+		assert_delta!(segment.a.distance_to(segment.b), 2.0, 1e-8);
+
+		let converted = ConvexPolygon::from(make_square());
+		assert_delta!(converted.vertices()[1].x, 1.0, EPSILON);
+		assert_delta!(converted.vertices()[1].y, 1.0, EPSILON);
+
+		// sides out of order, and too few sides
+		let l = Line { u: Vector { x: 1.0, y: 0.0 }, d: 1.0 };
+		let m = Line { u: Vector { x: 0.0, y: 1.0 }, d: 1.0 };
+		let n = Line { u: Vector { x: -1.0, y: 0.0 }, d: 0.0 };
+		let o = Line { u: Vector { x: 0.0, y: -1.0 }, d: 0.0 };
+		assert_eq!(ConvexPolygon::new(vec![l, m]).err(), Some(PolygonError::TooFewSides));
+		assert_eq!(ConvexPolygon::new(vec![l, n, m, o]).is_err(), true);
+		assert_eq!(ConvexPolygon::new(vec![l, m, n, o]).is_ok(), true);
+
+		// the axioms accept any boundary, including a borrowed polygon
+		let center = Vector { x: 0.0, y: 0.0 };
+		let corner = Vector { x: 1.0, y: 0.0 };
+		let outside = Vector { x: 0.0, y: 0.9 };
+		assert_eq!(axioms::axiom1(center, corner, &hexagon).len(), 1);
+		assert_eq!(axioms::axiom2(center, outside, &hexagon).len(), 0);
+		let ax2 = axioms::axiom2(Vector { x: 0.5, y: 1.4 }, Vector { x: 0.5, y: 0.0 }, a4);
+		assert_eq!(ax2.len(), 1);
+		assert_delta!(ax2[0].d, -0.7, 1e-8);
+	}
+
+	#[test]
+	fn polygon_with_holes_tests () {
+		let v = |x: f64, y: f64| Vector { x, y };
+		// an L-shaped sheet, the top right quarter is missing
+		let l_shape = Polygon::new(
+			&[v(0.0, 0.0), v(2.0, 0.0), v(2.0, 1.0), v(1.0, 1.0), v(1.0, 2.0), v(0.0, 2.0)],
+			&[]).unwrap();
+		assert_eq!(l_shape.contains(v(0.5, 1.5)), true);
+		assert_eq!(l_shape.contains(v(1.5, 1.5)), false);
+		assert_eq!(l_shape.contains(v(1.0, 1.5)), true);
+		let diagonal = Line { u: v(1.0, 1.0).normalize(), d: 2.5 / 2.0_f64.sqrt() };
+		let pieces = l_shape.clip(diagonal);
+		assert_eq!(pieces.len(), 2);
+		assert_delta!(pieces[0].a.distance_to(pieces[0].b), 0.5_f64.sqrt(), 1e-8);
+		assert_delta!(pieces[1].a.distance_to(pieces[1].b), 0.5_f64.sqrt(), 1e-8);
+
+		// the reflection of one line onto the other has to happen on the paper
+		let a = Line { u: v(1.0, 0.0), d: 1.5 };
+		let b = Line { u: v(0.0, 1.0), d: 1.5 };
+		let square = Polygon::new(&[v(0.0, 0.0), v(2.0, 0.0), v(2.0, 2.0), v(0.0, 2.0)], &[]).unwrap();
+		assert_eq!(axioms::axiom3(a, b, &square).len(), 2);
+		let ax3 = axioms::axiom3(a, b, &l_shape);
+		assert_eq!(ax3.len(), 1);
+		assert_delta!(ax3[0].d, 0.0, 1e-8);
+
+		// a square with a square window cut out of the middle
+		let window = Polygon::new(
+			&[v(0.0, 0.0), v(3.0, 0.0), v(3.0, 3.0), v(0.0, 3.0)],
+			&[vec![v(1.0, 1.0), v(2.0, 1.0), v(2.0, 2.0), v(1.0, 2.0)]]).unwrap();
+		assert_eq!(window.contains(v(1.5, 1.5)), false);
+		assert_eq!(window.contains(v(0.5, 1.5)), true);
+		assert_eq!(window.clip(Line { u: v(0.0, 1.0), d: 1.5 }).len(), 2);
+		// folding the point across the window is impossible, the crease is missing
+		assert_eq!(axioms::axiom2(v(0.5, 1.5), v(2.5, 1.5), &window).len(), 0);
+		assert_eq!(axioms::axiom2(v(0.5, 0.5), v(2.5, 0.5), &window).len(), 1);
+		assert_eq!(axioms::axiom5(v(1.5, 0.5), v(0.5, 1.5), Line { u: v(1.0, 0.0), d: 2.5 }, &window).len(), 0);
+		let solid = Polygon::new(&[v(0.0, 0.0), v(3.0, 0.0), v(3.0, 3.0), v(0.0, 3.0)], &[]).unwrap();
+		assert_eq!(axioms::axiom5(v(1.5, 0.5), v(0.5, 1.5), Line { u: v(1.0, 0.0), d: 2.5 }, &solid).len(), 1);
+		// the window is not a face, the sheet around it is the only one
+		let mut graph = Graph::from_polygon(&window);
+		assert_eq!(graph.faces_vertices.len(), 1);
+		assert_eq!(graph.faces_vertices[0].iter().all(|v| *v < 4), true);
+		// and stays that way when a crease crosses the window
+		graph.add_line(Line { u: v(0.0, 1.0), d: 1.5 }, Assignment::Valley);
+		assert_eq!(graph.faces_vertices.len(), 2);
+		let faces_contain = |p: Vector| (0..graph.faces_vertices.len()).any(|f| graph.face_contains(f, p));
+		assert_eq!(faces_contain(v(1.5, 1.2)), false);
+		assert_eq!(faces_contain(v(0.5, 1.2)), true);
+	}
+
+	#[test]
+	fn graph_tests () {
+		let mut graph = Graph::from(make_square());
+		assert_eq!(graph.vertices_coords.len(), 4);
+		assert_eq!(graph.edges_vertices.len(), 4);
+		assert_eq!(graph.faces_vertices.len(), 1);
+		// fold the square in half along the diagonal
+		let diagonal = axioms::axiom1(Vector { x: 0.0, y: 0.0 }, Vector { x: 1.0, y: 1.0 }, make_square());
+		let new_edges = graph.add_line(diagonal[0], Assignment::Valley);
+		assert_eq!(new_edges.len(), 1);
+		assert_eq!(graph.vertices_coords.len(), 4);
+		assert_eq!(graph.edges_vertices.len(), 5);
+		assert_eq!(graph.faces_vertices.len(), 2);
+		assert_eq!(graph.edges_faces()[new_edges[0]].len(), 2);
+		// book fold, crossing two boundary edges and the diagonal
+		let book = axioms::axiom2(Vector { x: 0.0, y: 0.0 }, Vector { x: 1.0, y: 0.0 }, make_square());
+		let new_edges = graph.add_line(book[0], Assignment::Mountain);
+		assert_eq!(new_edges.len(), 2);
+		assert_eq!(graph.vertices_coords.len(), 7);
+		assert_eq!(graph.edges_vertices.len(), 10);
+		assert_eq!(graph.faces_vertices.len(), 4);
+		let center = graph.nearest_vertex(Vector { x: 0.5, y: 0.5 }).unwrap();
+		assert_eq!(graph.vertices_vertices()[center].len(), 4);
+		assert_eq!(graph.vertices_edges()[center].len(), 4);
+		assert_eq!(graph.vertices_faces()[center].len(), 4);
+		assert_eq!(graph.edges_assignment.iter()
+			.filter(|a| **a == Assignment::Boundary).count(), 6);
+		// adding an existing crease again does nothing
+		assert_eq!(graph.add_line(book[0], Assignment::Mountain).len(), 0);
+		assert_eq!(Assignment::from_char('v'), Some(Assignment::Valley));
+		assert_eq!(Assignment::Mountain.flip(), Assignment::Valley);
+	}
+
+	#[test]
+	fn fold_tests () {
+		let text = r#"{
+			"file_spec": 1.1,
+			"file_creator": "a test",
+			"file_classes": ["singleModel"],
+			"frame_title": "diagonal",
+			"vertices_coords": [[0,0],[1,0],[1,1],[0,1,0]],
+			"edges_vertices": [[0,1],[1,2],[2,3],[3,0],[0,2]],
+			"edges_assignment": ["B","B","B","b","V"],
+			"edges_foldAngle": [0, 0, 0, 0, 180],
+			"faces_vertices": [[0,1,2],[0,2,3]],
+			"faceOrders": [[0,1,1]],
+			"vertices_vertices": [[1,2,3],[2,0],[3,0,1],[0,2]],
+			"file_frames": [{
+				"frame_classes": ["foldedForm"],
+				"frame_parent": 0,
+				"frame_inherit": true,
+				"vertices_coords": [[0,0,0],[1,0,0],[0.5,0.5,0.7],[1,0,0]]
+			}, {
+				"frame_parent": 0,
+				"frame_inherit": true,
+				"edges_vertices": [[1,3]]
+			}]
+		}"#;
+		let file = FoldFile::parse(text).unwrap();
+		assert_eq!(file.frames.len(), 3);
+		assert_eq!(file.metadata.len(), 3);
+		assert_eq!(file.frames[0].face_orders, Some(vec![(0, 1, 1)]));
+		assert_eq!(file.frames[0].other.len(), 2);
+		assert_eq!(file.frames[1].edges_vertices, None);
+		// 3D coordinates keep their z
+		assert_eq!(file.frames[1].vertices_z, Some(vec![0.0, 0.0, 0.7, 0.0]));
+		assert_eq!(file.frames[1].vertices_coords.as_ref().unwrap()[2], Vector { x: 0.5, y: 0.5 });
+		let graph = file.frames[0].to_graph().unwrap();
+		assert_eq!(graph.edges_assignment[4], Assignment::Valley);
+		assert_eq!(graph.faces_vertices.len(), 2);
+		// write it and read it back
+		let again = FoldFile::parse(&file.to_string_pretty()).unwrap();
+		assert_eq!(again, file);
+		assert_eq!(file.to_json()["file_frames"][0]["vertices_coords"][2][2], 0.7);
+		let from_graph = FoldFile::parse(&FoldFile::from_graph(&graph).to_string()).unwrap();
+		assert_eq!(from_graph.frames[0].to_graph().unwrap().edges_vertices, graph.edges_vertices);
+
+		match FoldFile::parse("{ \"vertices_coords\": [[0,0]], \"edges_vertices\": [[0,1]] }") {
+			Err(FoldError::IndexOutOfRange(key, 1, 1)) => assert_eq!(key, "edges_vertices"),
+			other => panic!("expected an index error, found {:?}", other)
+		}
+		// edges with no vertices at all, and a frame which can't be a graph
+		match FoldFile::parse("{ \"edges_vertices\": [[0,1]] }") {
+			Err(FoldError::IndexOutOfRange(key, 0, 0)) => assert_eq!(key, "edges_vertices"),
+			other => panic!("expected an index error, found {:?}", other)
+		}
+		match file.frames[2].to_graph() {
+			Err(FoldError::MissingField(key)) => assert_eq!(key, "vertices_coords"),
+			other => panic!("expected a missing field, found {:?}", other.map(|_| ()))
+		}
+		match FoldFile::parse("{ \"edges_vertices\": [[0,1]], \"vertices_coords\": [[0,0],[1,0]], \"edges_assignment\": [\"X\"] }") {
+			Err(FoldError::InvalidField(key, _)) => assert_eq!(key, "edges_assignment"),
+			other => panic!("expected a field error, found {:?}", other)
+		}
+		match FoldFile::parse("{ \"vertices_coords\": [[0,0],[1,0]], \"edges_vertices\": [[0,1]],\n \"edges_foldAngle\": [] }") {
+			Err(FoldError::LengthMismatch(_, 1, 0)) => (),
+			other => panic!("expected a length error, found {:?}", other)
+		}
+		match FoldFile::parse("{ \"vertices_coords\": [[0,0],\n ") {
+			Err(FoldError::Json(_, 2, _)) => (),
+			other => panic!("expected a json error, found {:?}", other)
+		}
+	}
+
+	#[test]
+	fn svg_tests () {
+		let square = make_square();
+		let mut svg = Svg::new();
+		svg.boundary(square);
+		svg.lines(&axioms::axiom2(Vector { x: 0.0, y: 0.0 }, Vector { x: 1.0, y: 0.0 }, square), square);
+		svg.point(Vector { x: 0.25, y: 0.75 });
+		let drawing = svg.to_string();
+		assert_eq!(drawing.starts_with("<svg"), true);
+		assert_eq!(drawing.contains("viewBox=\"-0.05 -0.05 1.1 1.1\""), true);
+		assert_eq!(drawing.contains("<line x1=\"0.5\""), true);
+		assert_eq!(drawing.matches("<circle").count(), 1);
+
+		let mut graph = Graph::from(square);
+		graph.add_line(Line { u: Vector { x: 1.0, y: 0.0 }, d: 0.5 }, Assignment::Valley);
+		let mut svg = Svg::new();
+		svg.view_box = Some([0.0, 0.0, 2.0, 2.0]);
+		svg.graph(&graph);
+		let drawing = svg.to_string();
+		assert_eq!(drawing.contains("viewBox=\"0 0 2 2\""), true);
+		assert_eq!(drawing.matches("<line").count(), 7);
+		assert_eq!(drawing.matches("stroke-dasharray=\"6 4\"").count(), 1);
+	}
+
+	#[test]
+	fn svg_import_tests () {
+		let text = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+			<g transform="scale(0.01)">
+				<rect x="0" y="0" width="100" height="100" fill="none" stroke="black"/>
+				<g style="stroke: #f00">
+					<line x1="0" y1="0" x2="100" y2="100"/>
+				</g>
+				<path d="M 0 100 l 100 -100" stroke="rgb(0, 0, 255)" stroke-dasharray="5 5"/>
+				<polyline points="50,0 50,50" stroke="#000" stroke-dasharray="6 3 2 3" transform="translate(0 0)"/>
+				<circle cx="50" cy="50" r="10" stroke="black"/>
+			</g>
+			<defs><line x1="0" y1="0" x2="1" y2="1" stroke="black"/></defs>
+		</svg>"##;
+		let table = AssignmentTable::default();
+		let segments = ear::svg::parse_segments(text, &table).unwrap();
+		assert_eq!(segments.len(), 7);
+		assert_delta!(segments[4].0.b.x, 1.0, EPSILON);
+		assert_eq!(segments[4].1, Assignment::Mountain);
+		assert_eq!(segments[5].1, Assignment::Valley);
+		assert_eq!(segments[6].1, Assignment::Mountain);
+		let graph = ear::svg::parse(text, &table).unwrap();
+		// the diagonals cross in the center, the polyline ends there
+		assert_eq!(graph.vertices_coords.len(), 6);
+		assert_eq!(graph.edges_vertices.len(), 10);
+		assert_eq!(graph.faces_vertices.len(), 5);
+		assert_eq!(graph.edges_assignment.iter()
+			.filter(|a| **a == Assignment::Boundary).count(), 5);
+
+		let broken = "<svg><line x1=\"zero\"/></svg>";
+		assert_eq!(ear::svg::parse(broken, &table).err(), Some(SvgError::InvalidAttribute(
+			"line".to_string(), "x1".to_string(), "zero".to_string())));
+		assert_eq!(ear::svg::parse("<svg>", &table).is_err(), true);
+	}
+
+	#[test]
+	fn oripa_tests () {
+		let cp = "1 -200 -200 200 -200\n1 200 -200 200 200\n1 200 200 -200 200\n\
+			1 -200 200 -200 -200\n3 -200 -200 200 200\n\n2 -200 200 200 -200\n";
+		let segments = ear::oripa::parse_cp(cp).unwrap();
+		assert_eq!(segments.len(), 6);
+		assert_delta!(segments[1].0.a.x, 1.0, EPSILON);
+		assert_delta!(segments[1].0.a.y, 0.0, EPSILON);
+		assert_eq!(segments[4].1, Assignment::Valley);
+		assert_eq!(segments[5].1, Assignment::Mountain);
+		let graph = Graph::from_segments(&segments);
+		assert_eq!(graph.faces_vertices.len(), 4);
+		assert_eq!(ear::oripa::parse_cp(&ear::oripa::write_cp(&segments)).unwrap().len(), 6);
+		assert_eq!(ear::oripa::parse_cp("2 0 0 1").err(), Some(ear::oripa::OripaError::Line(
+			1, "expected \"type x1 y1 x2 y2\"".to_string())));
+		assert_eq!(ear::oripa::parse_cp("1 0 0 0 0\n7 0 0 1 1").is_err(), true);
+
+		let opx = ear::oripa::write_opx(&graph.assigned_segments());
+		let from_opx = ear::oripa::parse_opx(&opx).unwrap();
+		assert_eq!(from_opx.len(), graph.edges_vertices.len());
+		for (i, (segment, assignment)) in from_opx.iter().enumerate() {
+			assert_eq!(*assignment, graph.edges_assignment[i]);
+			assert_delta!(segment.a.x, graph.edge_segment(i).a.x, EPSILON);
+			assert_delta!(segment.b.y, graph.edge_segment(i).b.y, EPSILON);
+		}
+		// the encoder leaves out values that are 0, and the paper can be any size
+		let sparse = r#"<?xml version="1.0" encoding="UTF-8"?>
+			<java version="1.5.0_05" class="java.beans.XMLDecoder">
+			<object class="oripa.DataSet"><void property="lines">
+			<array class="oripa.OriLineProxy" length="1"><void index="0">
+			<object class="oripa.OriLineProxy">
+			<void property="type"><int>2</int></void>
+			<void property="x1"><double>100.0</double></void>
+			</object></void></array></void>
+			<void property="paperSize"><double>200.0</double></void>
+			</object></java>"#;
+		let from_sparse = ear::oripa::parse_opx(sparse).unwrap();
+		assert_eq!(from_sparse[0].1, Assignment::Mountain);
+		assert_delta!(from_sparse[0].0.a.x, 0.5, EPSILON);
+		assert_delta!(from_sparse[0].0.b.x, 1.0, EPSILON);
+	}
+
+	#[test]
+	fn planarize_tests () {
+		let v = |x: f64, y: f64| Vector { x, y };
+		let s = |a: Vector, b: Vector| Segment { a, b };
+		let mut segments: Vec<(Segment, Assignment)> = make_square().vertices().iter()
+			.enumerate()
+			.map(|(i, p)| (s(*p, make_square().vertices()[(i + 1) % 4]), Assignment::Boundary))
+			.collect();
+		// two fold lines which both cross the diagonals in the center
+		segments.push((s(v(0.0, 0.0), v(1.0, 1.0)), Assignment::Valley));
+		segments.push((s(v(1.0, 0.0), v(0.0, 1.0)), Assignment::Mountain));
+		// a crease drawn in two overlapping pieces
+		segments.push((s(v(0.5, 0.0), v(0.5, 0.7)), Assignment::Mountain));
+		segments.push((s(v(0.5, 0.3), v(0.5, 1.0)), Assignment::Unassigned));
+		// a flat line on top of the boundary, and an endpoint that misses
+		// the center by less than the tolerance
+		segments.push((s(v(0.0, 0.0), v(0.5, 0.0)), Assignment::Flat));
+		segments.push((s(v(0.0, 0.5), v(0.5 - 1e-9, 0.5 + 1e-9)), Assignment::Valley));
+		let graph = ear::planarize(&segments, &ear::Tolerance { distance: 1e-6, angle: 1e-6, ..ear::Tolerance::default() });
+		// the ends of the overlapping pieces (0.3 and 0.7) remain as vertices
+		assert_eq!(graph.vertices_coords.len(), 10);
+		assert_eq!(graph.edges_vertices.len(), 16);
+		assert_eq!(graph.faces_vertices.len(), 7);
+		let center = graph.nearest_vertex(v(0.5, 0.5)).unwrap();
+		assert_eq!(graph.vertices_edges()[center].len(), 7);
+		// the overlapping pieces became edges of the mountain fold
+		let upper = graph.nearest_vertex(v(0.5, 0.7)).unwrap();
+		let lower = graph.nearest_vertex(v(0.5, 0.3)).unwrap();
+		let bottom = graph.nearest_vertex(v(0.5, 0.0)).unwrap();
+		assert_eq!(graph.edges_assignment[graph.edge_between(center, upper).unwrap()], Assignment::Mountain);
+		assert_eq!(graph.edges_assignment[graph.edge_between(center, lower).unwrap()], Assignment::Mountain);
+		let corner = graph.nearest_vertex(v(0.0, 0.0)).unwrap();
+		assert_eq!(graph.edges_assignment[graph.edge_between(corner, bottom).unwrap()], Assignment::Boundary);
+		assert_eq!(ear::resolve_assignment(Assignment::Mountain, Assignment::Valley), Assignment::Unassigned);
+		// with no tolerance at all, exactly equal points and exactly parallel
+		// segments are still found
+		let exact = ear::Tolerance { distance: 0.0, angle: 0.0, ..ear::Tolerance::default() };
+		let overlapping = [
+			(s(v(0.0, 0.0), v(1.0, 0.0)), Assignment::Valley),
+			(s(v(0.5, 0.0), v(1.5, 0.0)), Assignment::Valley),
+			(s(v(1.5, 0.0), v(1.5, 1.0)), Assignment::Mountain)
+		];
+		let graph = ear::planarize(&overlapping, &exact);
+		assert_eq!(graph.vertices_coords.len(), 5);
+		assert_eq!(graph.edges_vertices.len(), 4);
+	}
+
+	#[test]
+	fn vertex_tests () {
+		let v = |x: f64, y: f64| Vector { x, y };
+		let s = |a: Vector, b: Vector| Segment { a, b };
+		assert_delta!(v(1.0, 0.0).angle_to(v(0.0, -1.0)), 1.5 * std::f64::consts::PI, EPSILON);
+		let square: Vec<(Segment, Assignment)> = make_square().vertices().iter()
+			.enumerate()
+			.map(|(i, p)| (s(*p, make_square().vertices()[(i + 1) % 4]), Assignment::Boundary))
+			.collect();
+		let center = v(0.5, 0.5);
+		// a plus sign, three mountains and a valley
+		let mut segments = square.clone();
+		segments.push((s(center, v(1.0, 0.5)), Assignment::Mountain));
+		segments.push((s(center, v(0.5, 1.0)), Assignment::Mountain));
+		segments.push((s(center, v(0.0, 0.5)), Assignment::Mountain));
+		segments.push((s(center, v(0.5, 0.0)), Assignment::Valley));
+		let mut graph = Graph::from_segments(&segments);
+		let report = ear::vertex::check(&graph);
+		// the vertices on the paper's edge are left out
+		assert_eq!(report.vertices.len(), 1);
+		assert_eq!(report.vertices[0].creases.len(), 4);
+		assert_delta!(report.vertices[0].sector_angles[0], std::f64::consts::FRAC_PI_2, EPSILON);
+		assert_eq!(report.is_flat_foldable(), true);
+		// two mountains and two valleys
+		let c = graph.nearest_vertex(center).unwrap();
+		let left = graph.nearest_vertex(v(0.0, 0.5)).unwrap();
+		let edge = graph.edge_between(c, left).unwrap();
+		graph.edges_assignment[edge] = Assignment::Valley;
+		let report = ear::vertex::check(&graph);
+		assert_eq!(report.vertices[0].maekawa, Some(-2));
+		assert_eq!(report.maekawa_violations().len(), 1);
+		assert_eq!(report.kawasaki_violations().len(), 0);
+		// unassigned creases can't break Maekawa's theorem
+		graph.edges_assignment[edge] = Assignment::Unassigned;
+		assert_eq!(ear::vertex::check(&graph).vertices[0].maekawa, None);
+		// sectors of 90, 45, 135, and 90 degrees
+		let mut segments = square.clone();
+		segments.push((s(center, v(1.0, 0.5)), Assignment::Mountain));
+		segments.push((s(center, v(0.5, 1.0)), Assignment::Mountain));
+		segments.push((s(center, v(0.0, 1.0)), Assignment::Mountain));
+		segments.push((s(center, v(0.5, 0.0)), Assignment::Valley));
+		let report = ear::vertex::check(&Graph::from_segments(&segments));
+		assert_eq!(report.kawasaki_violations().len(), 1);
+		assert_delta!(report.vertices[0].kawasaki.unwrap().abs(), std::f64::consts::FRAC_PI_4, 1e-12);
+		assert_eq!(report.is_flat_foldable(), false);
+		// an odd number of creases never folds flat
+		let mut segments = square.clone();
+		segments.push((s(center, v(1.0, 0.5)), Assignment::Mountain));
+		segments.push((s(center, v(0.0, 1.0)), Assignment::Mountain));
+		segments.push((s(center, v(0.0, 0.0)), Assignment::Valley));
+		let report = ear::vertex::check(&Graph::from_segments(&segments));
+		assert_eq!(report.vertices[0].kawasaki, None);
+		assert_eq!(report.kawasaki_violations().len(), 1);
+	}
+
+	#[test]
+	fn kawasaki_solver_tests () {
+		let v = |x: f64, y: f64| Vector { x, y };
+		let s = |a: Vector, b: Vector| Segment { a, b };
+		let center = v(0.5, 0.5);
+		// sectors of 90, 135, and 135 degrees starting from the bottom, any can be split
+		let mut segments: Vec<(Segment, Assignment)> = make_square().vertices().iter()
+			.enumerate()
+			.map(|(i, p)| (s(*p, make_square().vertices()[(i + 1) % 4]), Assignment::Boundary))
+			.collect();
+		segments.push((s(center, v(1.0, 0.5)), Assignment::Mountain));
+		segments.push((s(center, v(0.0, 1.0)), Assignment::Mountain));
+		segments.push((s(center, v(0.5, 0.0)), Assignment::Valley));
+		let graph = Graph::from_segments(&segments);
+		let c = graph.nearest_vertex(center).unwrap();
+		let solutions = ear::vertex::missing_creases(&graph, c, make_square());
+		assert_eq!(solutions.len(), 3);
+		for (solution, end) in solutions.iter().zip([v(1.0, 0.0), v(1.0, 1.0), v(0.0, 0.0)].iter()) {
+			assert_eq!(solution.a.equivalent(center), true);
+			assert_eq!(solution.b.equivalent(*end), true);
+		}
+		// each solution completes the vertex
+		for solution in solutions.iter() {
+			let mut graph = graph.clone();
+			graph.add_segment(*solution, Assignment::Valley);
+			assert_eq!(ear::vertex::check(&graph).kawasaki_violations().len(), 0);
+		}
+		// a single crease continues straight through
+		let directions = ear::vertex::kawasaki_directions(&[v(0.0, 1.0)]);
+		assert_eq!(directions.len(), 1);
+		assert_eq!(directions[0].equivalent(v(0.0, -1.0)), true);
+		// an even number of creases can't be completed with one more
+		assert_eq!(ear::vertex::kawasaki_directions(&[v(1.0, 0.0), v(-1.0, 0.0)]).len(), 0);
+		// a vertex on the paper's edge
+		let corner = graph.nearest_vertex(v(0.0, 1.0)).unwrap();
+		assert_eq!(ear::vertex::missing_creases(&graph, corner, make_square()).len(), 0);
+	}
+
+	#[test]
+	fn assignment_tests () {
+		use ear::vertex::{valid_assignments, count_assignments, is_valid_assignment};
+		let (m, v, u) = (Assignment::Mountain, Assignment::Valley, Assignment::Unassigned);
+		let deg = |d: f64| d.to_radians();
+		// four equal sectors, any assignment with three of one kind works
+		let square = [deg(90.0); 4];
+		assert_eq!(count_assignments(&square, &[u, u, u, u]), 8);
+		assert_eq!(is_valid_assignment(&square, &[m, m, v, m]), true);
+		assert_eq!(is_valid_assignment(&square, &[m, v, m, v]), false);
+		// the 45 degree sector is the smallest, the creases around it
+		// must be different (big-little-big)
+		let sectors = [deg(45.0), deg(90.0), deg(135.0), deg(90.0)];
+		let solutions = valid_assignments(&sectors, &[u, u, u, u]);
+		assert_eq!(solutions.len(), 4);
+		assert_eq!(solutions.iter().all(|s| s[0] != s[1] && s[2] == s[3]), true);
+		assert_eq!(is_valid_assignment(&sectors, &[m, m, m, v]), false);
+		assert_eq!(count_assignments(&sectors, &[u, u, m, u]), 2);
+		// not flat-foldable at all
+		assert_eq!(count_assignments(&[deg(100.0), deg(90.0), deg(90.0), deg(80.0)], &[u, u, u, u]), 0);
+		// suggest assignments for the unassigned creases of a crease pattern
+		let p = |x: f64, y: f64| Vector { x, y };
+		let s = |a: Vector, b: Vector| Segment { a, b };
+		let center = p(0.5, 0.5);
+		let mut segments: Vec<(Segment, Assignment)> = make_square().vertices().iter()
+			.enumerate()
+			.map(|(i, q)| (s(*q, make_square().vertices()[(i + 1) % 4]), Assignment::Boundary))
+			.collect();
+		segments.push((s(center, p(1.0, 0.5)), u));
+		segments.push((s(center, p(0.5, 1.0)), u));
+		segments.push((s(center, p(0.0, 0.5)), u));
+		segments.push((s(center, p(0.5, 0.0)), v));
+		let graph = Graph::from_segments(&segments);
+		let c = graph.nearest_vertex(center).unwrap();
+		let suggestions = ear::vertex::vertex_assignments(&graph, c);
+		assert_eq!(suggestions.len(), 4);
+		for suggestion in suggestions.iter() {
+			let mut graph = graph.clone();
+			for (e, assignment) in suggestion.iter() { graph.edges_assignment[*e] = *assignment; }
+			assert_eq!(ear::vertex::check(&graph).is_flat_foldable(), true);
+		}
+	}
+
+	#[test]
+	fn layer_tests () {
+		use ear::layer::{solve, solve_all, LayerError, Constraint};
+		let v = |x: f64, y: f64| Vector { x, y };
+		// a strip cut into pieces by vertical creases, and folded like an
+		// accordion: each piece is reflected across the creases before it
+		let strip = |width: f64, creases: &[(f64, Assignment)]| {
+			let mut graph = Graph::from(make_rect(width, 1.0));
+			for (x, assignment) in creases.iter() {
+				graph.add_line(Line { u: v(1.0, 0.0), d: *x }, *assignment);
+			}
+			let fold_x = |x: f64| {
+				let (mut start, mut position, mut direction) = (0.0, 0.0, 1.0);
+				for (crease, _) in creases.iter() {
+					if x <= *crease + 1e-6 { break; }
+					position += direction * (crease - start);
+					start = *crease;
+					direction = -direction;
+				}
+				position + direction * (x - start)
+			};
+			let folded: Vec<Vector> = graph.vertices_coords.iter().map(|p| v(fold_x(p.x), p.y)).collect();
+			(graph, folded)
+		};
+		// pieces 2, 1, 2 wide. the little piece between two big ones must
+		// be folded with a mountain and a valley
+		let (graph, folded) = strip(5.0, &[(2.0, Assignment::Mountain), (3.0, Assignment::Valley)]);
+		let a = (0..graph.faces_vertices.len()).find(|f| graph.face_contains(*f, v(1.0, 0.5))).unwrap();
+		let b = (0..graph.faces_vertices.len()).find(|f| graph.face_contains(*f, v(2.5, 0.5))).unwrap();
+		let orders = solve(&graph, &folded).unwrap();
+		assert_eq!(orders.len(), 3);
+		assert_eq!(solve_all(&graph, &folded).unwrap().len(), 1);
+		// the middle piece is flipped and under the first
+		let order = orders.iter().find(|(f, g, _)| (*f, *g) == (a.min(b), a.max(b))).unwrap();
+		assert_eq!(order.2, if a < b { -1 } else { 1 });
+		let (graph, folded) = strip(5.0, &[(2.0, Assignment::Mountain), (3.0, Assignment::Mountain)]);
+		match solve(&graph, &folded) {
+			Err(LayerError::Infeasible(Some(Constraint::TacoTortilla(_, _)))) => (),
+			other => panic!("expected a taco-tortilla contradiction, found {:?}", other)
+		}
+		// three equal pieces and two valleys, the last piece can tuck
+		// inside or wrap around the outside
+		let (graph, folded) = strip(3.0, &[(1.0, Assignment::Valley), (2.0, Assignment::Valley)]);
+		assert_eq!(solve_all(&graph, &folded).unwrap().len(), 2);
+		// four pieces, the first and last creases are folded along the same line.
+		// of the five stackings which keep each crease's order, two interleave them
+		let valley = Assignment::Valley;
+		let (graph, folded) = strip(4.0, &[(1.0, valley), (2.0, valley), (3.0, valley)]);
+		assert_eq!(solve_all(&graph, &folded).unwrap().len(), 3);
+		// the folded points must match the crease pattern
+		assert_eq!(solve(&graph, &folded[1..]).err(), Some(LayerError::VertexCount(folded.len(), folded.len() - 1)));
+		let mut unfolded = graph.clone();
+		unfolded.edges_assignment.iter_mut().for_each(|a| if *a == Assignment::Valley { *a = Assignment::Flat });
+		assert_eq!(matches!(solve(&unfolded, &folded), Err(LayerError::CreaseMismatch(_))), true);
+	}
+
+	#[test]
+	fn folded_tests () {
+		let v = |x: f64, y: f64| Vector { x, y };
+		let mirror = ear::Matrix::reflection(Line { u: v(1.0, 0.0), d: 0.5 });
+		assert_eq!(mirror.transform(v(1.0, 0.25)).equivalent(v(0.0, 0.25)), true);
+		assert_eq!(mirror.multiply(mirror).equivalent(ear::Matrix::IDENTITY), true);
+		assert_eq!(mirror.inverse().unwrap().equivalent(mirror), true);
+		// fold a square in half with a valley, then in half again
+		let mut graph = Graph::from(make_square());
+		graph.add_line(Line { u: v(1.0, 0.0), d: 0.5 }, Assignment::Valley);
+		graph.add_segment(Segment { a: v(0.0, 0.5), b: v(0.5, 0.5) }, Assignment::Mountain);
+		graph.add_segment(Segment { a: v(0.5, 0.5), b: v(1.0, 0.5) }, Assignment::Valley);
+		let root = (0..graph.faces_vertices.len()).find(|f| graph.face_contains(*f, v(0.25, 0.25))).unwrap();
+		let matrices = ear::folded::faces_matrix(&graph, root);
+		assert_eq!(matrices.iter().all(|m| m.is_some()), true);
+		assert_eq!(matrices.iter().filter(|m| m.unwrap().flips()).count(), 2);
+		let folded = ear::folded::vertices_coords_folded(&graph, root);
+		// everything lands on the bottom left quarter
+		assert_eq!(folded.iter().all(|p| p.x > -EPSILON && p.x < 0.5 + EPSILON && p.y > -EPSILON && p.y < 0.5 + EPSILON), true);
+		assert_eq!(folded[graph.nearest_vertex(v(1.0, 1.0)).unwrap()].equivalent(v(0.0, 0.0)), true);
+		assert_eq!(folded[graph.nearest_vertex(v(0.5, 1.0)).unwrap()].equivalent(v(0.5, 0.0)), true);
+		// the folded form has a stacking order
+		assert_eq!(ear::vertex::check(&graph).is_flat_foldable(), true);
+		assert_eq!(ear::layer::solve(&graph, &folded).is_ok(), true);
+	}
+
+	#[test]
+	fn flat_fold_tests () {
+		use ear::model::{Model, Side};
+		let v = |x: f64, y: f64| Vector { x, y };
+		let mut model = Model::from(Graph::from(make_square()));
+		// fold the right half over onto the left
+		let creases = model.flat_fold(Line { u: v(1.0, 0.0), d: 0.5 }, Assignment::Valley, Side::Positive);
+		assert_eq!(creases.len(), 1);
+		assert_eq!(model.crease_pattern.faces_vertices.len(), 2);
+		assert_eq!(model.crease_pattern.edges_assignment[creases[0]], Assignment::Valley);
+		let corner = model.crease_pattern.nearest_vertex(v(1.0, 1.0)).unwrap();
+		assert_eq!(model.vertices_coords_folded[corner].equivalent(v(0.0, 1.0)), true);
+		let right = (0..2).find(|f| model.crease_pattern.face_contains(*f, v(0.75, 0.5))).unwrap();
+		assert_eq!(model.face_flipped(right), true);
+		assert_eq!(model.faces_layer[right], 1);
+		// fold the top half down, through both layers. the face which is
+		// upside down gets a mountain crease
+		let creases = model.flat_fold(Line { u: v(0.0, 1.0), d: 0.5 }, Assignment::Valley, Side::Positive);
+		assert_eq!(creases.len(), 2);
+		let graph = &model.crease_pattern;
+		assert_eq!(graph.faces_vertices.len(), 4);
+		let center = graph.nearest_vertex(v(0.5, 0.5)).unwrap();
+		let left = graph.edge_between(center, graph.nearest_vertex(v(0.0, 0.5)).unwrap()).unwrap();
+		let right = graph.edge_between(center, graph.nearest_vertex(v(1.0, 0.5)).unwrap()).unwrap();
+		assert_eq!(graph.edges_assignment[left], Assignment::Valley);
+		assert_eq!(graph.edges_assignment[right], Assignment::Mountain);
+		assert_eq!(model.vertices_coords_folded[graph.nearest_vertex(v(1.0, 1.0)).unwrap()].equivalent(v(0.0, 0.0)), true);
+		let top_left = (0..4).find(|f| graph.face_contains(*f, v(0.25, 0.75))).unwrap();
+		assert_eq!(model.faces_layer[top_left], 3);
+		// the crease pattern is flat-foldable, and the layer solver agrees
+		assert_eq!(ear::vertex::check(graph).is_flat_foldable(), true);
+		let mut orders = model.face_orders();
+		orders.sort();
+		let solutions = ear::layer::solve_all(graph, &model.vertices_coords_folded).unwrap();
+		assert_eq!(solutions.iter().any(|s| { let mut s = s.clone(); s.sort(); s == orders }), true);
+		// a model can be made from a crease pattern, and keeps its stacking
+		let folded = Model::new(graph.clone()).unwrap();
+		assert_eq!(folded.faces_layer.len(), 4);
+		// a mountain folds the left half underneath
+		let mut model = Model::from(Graph::from(make_square()));
+		let creases = model.flat_fold(Line { u: v(1.0, 0.0), d: 0.5 }, Assignment::Mountain, Side::Negative);
+		assert_eq!(model.crease_pattern.edges_assignment[creases[0]], Assignment::Mountain);
+		let left = (0..2).find(|f| model.crease_pattern.face_contains(*f, v(0.25, 0.5))).unwrap();
+		assert_eq!(model.faces_layer[left], 0);
+		assert_eq!(model.vertices_coords_folded[model.crease_pattern.nearest_vertex(v(0.0, 0.0)).unwrap()].equivalent(v(1.0, 0.0)), true);
+	}
+
+	#[test]
+	fn fold_flap_tests () {
+		use ear::model::{Model, Side, FlapError};
+		let v = |x: f64, y: f64| Vector { x, y };
+		let half = |model: &mut Model| {
+			model.flat_fold(Line { u: v(1.0, 0.0), d: 0.5 }, Assignment::Valley, Side::Positive);
+		};
+		// fold the corner of only the top layer
+		let mut model = Model::from(Graph::from(make_square()));
+		half(&mut model);
+		let u = v(1.0, -1.0).normalize();
+		let corner = Line { u, d: v(0.0, 0.75).dot(u) };
+		let creases = model.fold_flap(corner, Assignment::Valley, v(0.05, 0.95)).unwrap();
+		assert_eq!(creases.len(), 1);
+		assert_eq!(model.crease_pattern.faces_vertices.len(), 3);
+		// the top layer is upside down, in the crease pattern it's a mountain
+		assert_eq!(model.crease_pattern.edges_assignment[creases[0]], Assignment::Mountain);
+		let graph = &model.crease_pattern;
+		assert_eq!(model.vertices_coords_folded[graph.nearest_vertex(v(1.0, 1.0)).unwrap()].equivalent(v(0.25, 0.75)), true);
+		assert_eq!(model.vertices_coords_folded[graph.nearest_vertex(v(0.0, 1.0)).unwrap()].equivalent(v(0.0, 1.0)), true);
+		let tip = (0..3).find(|f| graph.face_contains(*f, v(0.95, 0.95))).unwrap();
+		assert_eq!(model.faces_layer[tip], 2);
+		// across the spine the bottom layer is connected, both layers fold
+		let mut model = Model::from(Graph::from(make_square()));
+		half(&mut model);
+		let creases = model.fold_flap(Line { u: v(0.0, 1.0), d: 0.75 }, Assignment::Valley, v(0.25, 0.9)).unwrap();
+		assert_eq!(creases.len(), 2);
+		assert_eq!(model.crease_pattern.faces_vertices.len(), 4);
+		// fold in half twice, the left layers can't fold without the
+		// right layers which are sandwiched between them
+		let mut model = Model::from(Graph::from(make_square()));
+		half(&mut model);
+		model.flat_fold(Line { u: v(0.0, 1.0), d: 0.5 }, Assignment::Valley, Side::Positive);
+		let before = model.crease_pattern.edges_vertices.len();
+		let result = model.fold_flap(Line { u: v(1.0, 0.0), d: 0.25 }, Assignment::Valley, v(0.1, 0.1));
+		assert_eq!(matches!(result, Err(FlapError::Blocked(_))), true);
+		assert_eq!(model.crease_pattern.edges_vertices.len(), before);
+		assert_eq!(model.fold_flap(Line { u: v(1.0, 0.0), d: 0.25 }, Assignment::Valley, v(0.25, 0.1)).err(), Some(FlapError::OnLine));
+		assert_eq!(model.fold_flap(Line { u: v(1.0, 0.0), d: 0.25 }, Assignment::Valley, v(2.0, 0.1)).err(), Some(FlapError::NotOnPaper));
+		// a strip folded over on top lies where the flap would land, a valley
+		// would have to wrap the flap around it, a mountain goes underneath
+		let mut model = Model::from(Graph::from(make_square()));
+		model.flat_fold(Line { u: v(1.0, 0.0), d: 0.75 }, Assignment::Valley, Side::Positive);
+		let result = model.fold_flap(Line { u: v(1.0, 0.0), d: 0.4 }, Assignment::Valley, v(0.1, 0.5));
+		assert_eq!(matches!(result, Err(FlapError::Blocked(_))), true);
+		assert_eq!(model.fold_flap(Line { u: v(1.0, 0.0), d: 0.4 }, Assignment::Mountain, v(0.1, 0.5)).is_ok(), true);
+	}
+
+	#[test]
+	fn reference_tests () {
+		use ear::reference::{Database, LineSource, PointSource};
+		let v = |x: f64, y: f64| Vector { x, y };
+		let database = Database::build(make_square(), 2);
+		assert_eq!(database.points.iter().filter(|p| p.source == PointSource::Corner).count(), 4);
+		assert_eq!(database.lines.iter().filter(|l| l.source == LineSource::Edge).count(), 4);
+		assert_eq!(database.points.iter().all(|p| p.rank <= 2), true);
+		// the center is where the two diagonals cross
+		let center = database.find_point(v(0.5, 0.5), 3);
+		assert_eq!(center.len(), 3);
+		assert_delta!(center[0].error, 0.0, 1e-12);
+		assert_eq!(center[0].folds, 2);
+		assert_eq!(center[0].steps.len(), 2);
+		// a quarter along the bottom: fold in half, then fold the corner to the middle
+		let quarter = database.find_point(v(0.25, 0.0), 1);
+		assert_delta!(quarter[0].error, 0.0, 1e-12);
+		assert_eq!(quarter[0].folds, 2);
+		// each step only uses lines folded before it
+		let steps = &quarter[0].steps;
+		assert_eq!(database.line_steps(steps[1]), *steps);
+		// results are ordered by error
+		let near = database.find_point(v(0.3, 0.3), 5);
+		assert_eq!(near.windows(2).all(|w| w[0].error <= w[1].error), true);
+		assert_eq!(near[0].error > 0.0, true);
+		// the vertical book fold takes one fold
+		let book = database.find_line(Line { u: v(-1.0, 0.0), d: -0.5 }, make_square(), 1);
+		assert_delta!(book[0].error, 0.0, 1e-12);
+		assert_eq!(book[0].folds, 1);
+		// an edge is already there
+		let edge = database.find_line(Line { u: v(0.0, 1.0), d: 0.0 }, make_square(), 1);
+		assert_eq!(edge[0].folds, 0);
+		assert_eq!(edge[0].steps.is_empty(), true);
+	}
+
+	#[test]
+	fn reference_cache_tests () {
+		use ear::reference::{Database, CacheError, paper_key, cache_path};
+		let directory = std::env::temp_dir().join(format!("rabbit-ear-cache-{}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&directory);
+		let square = make_square();
+		let built = Database::build(square, 2);
+		// the same paper always has the same key, a different paper doesn't
+		assert_eq!(paper_key(square), paper_key(make_rect(1.0, 1.0)));
+		assert_eq!(paper_key(square) == paper_key(make_rect(2.0, 1.0)), false);
+		let path = cache_path(&directory, square, 2);
+		assert_eq!(path.exists(), false);
+		let cached = Database::cached(square, 2, &directory).unwrap();
+		assert_eq!(path.exists(), true);
+		assert_eq!(cached.to_bytes(0), built.to_bytes(0));
+		// the second time it's read from the file
+		let (read, paper) = Database::read(&path).unwrap();
+		assert_eq!(paper, paper_key(square));
+		assert_eq!(read.depth, 2);
+		assert_eq!(read.to_bytes(paper), built.to_bytes(paper));
+		let center = read.find_point(Vector { x: 0.5, y: 0.5 }, 1);
+		assert_eq!(center[0].folds, 2);
+		assert_eq!(Database::cached(square, 2, &directory).unwrap().lines.len(), built.lines.len());
+		// broken files
+		let mut bytes = built.to_bytes(paper);
+		assert_eq!(matches!(Database::from_bytes(&bytes[..bytes.len() - 1]), Err(CacheError::Corrupt(_))), true);
+		assert_eq!(matches!(Database::from_bytes(b"not a database at all, not at all"), Err(CacheError::NotACache)), true);
+		bytes[8] = 9;
+		assert_eq!(matches!(Database::from_bytes(&bytes), Err(CacheError::Version(9))), true);
+		let _ = std::fs::remove_dir_all(&directory);
+	}
+
+	#[test]
+	fn axiom_solve_tests () {
+		use ear::axioms::{solve, Axiom};
+		let v = |x: f64, y: f64| Vector { x, y };
+		let square = make_square();
+		let two = solve(&Axiom::Axiom2(v(0.0, 0.0), v(1.0, 1.0)), square).unwrap();
+		assert_eq!(two.len(), 1);
+		assert_eq!(two[0].axiom.number(), 2);
+		assert_eq!(two[0].mirrors.len(), 1);
+		assert_eq!(two[0].mirrors[0].1.equivalent(v(1.0, 1.0)), true);
+		assert_eq!(two[0].line.equivalent(axioms::axiom2(v(0.0, 0.0), v(1.0, 1.0), square)[0]), true);
+		// both bisectors of a cross are on the paper
+		let x = Line { u: v(1.0, 0.0), d: 0.5 };
+		let y = Line { u: v(0.0, 1.0), d: 0.5 };
+		let three = solve(&Axiom::Axiom3(x, y), square).unwrap();
+		assert_eq!(three.iter().map(|s| s.branch).collect::<Vec<usize>>(), vec![0, 1]);
+		// only the second of the two mirror points is on the paper
+		let line = Line { u: v(0.0, 1.0), d: 0.5 };
+		let five = solve(&Axiom::Axiom5(v(0.0, 0.0), v(1.0, 0.0), line), square).unwrap();
+		assert_eq!(five.len(), 1);
+		assert_eq!(five[0].branch, 1);
+		let (point, mirror) = five[0].mirrors[0];
+		assert_eq!(point.equivalent(v(1.0, 0.0)), true);
+		assert_delta!(mirror.y, 0.5, 1e-12);
+		assert_delta!(mirror.magnitude(), 1.0, 1e-12);
+		// each point lands on its line, across the fold
+		let l1 = Line { u: v(0.0, 1.0), d: 1.0 };
+		let l2 = Line { u: v(1.0, 0.0), d: 1.0 };
+		let six = solve(&Axiom::Axiom6(v(0.2, 0.7), v(0.6, 0.2), l1, l2), square).unwrap();
+		assert_eq!(six.is_empty(), false);
+		for solution in six.iter() {
+			assert_eq!(solution.mirrors.len(), 2);
+			for (i, (point, mirror)) in solution.mirrors.iter().enumerate() {
+				let target = if i == 0 { l1 } else { l2 };
+				assert_delta!(mirror.dot(target.u), target.d, 1e-6);
+				assert_delta!(solution.line.reflect_vector(*point).distance_to(*mirror), 0.0, 1e-6);
+			}
+		}
+		let seven = solve(&Axiom::Axiom7(v(0.3, 0.2), y, l2), square).unwrap();
+		assert_eq!(seven.len(), 1);
+		assert_delta!(seven[0].mirrors[0].1.x, 1.0, 1e-12);
+	}
+
+	#[test]
+	fn axiom_verify_tests () {
+		use ear::axioms::{solve, verify, Axiom};
+		let v = |x: f64, y: f64| Vector { x, y };
+		let square = make_square();
+		// a line which isn't a solution is off by how far the point misses
+		let a = v(0.0, 0.0);
+		let b = v(1.0, 1.0);
+		assert_delta!(verify(&Axiom::Axiom2(a, b), Line { u: v(1.0, 1.0).normalize(), d: 2.0_f64.sqrt() / 2.0 }), 0.0, 1e-12);
+		assert_delta!(verify(&Axiom::Axiom2(a, b), Line { u: v(1.0, 0.0), d: 0.5 }), 1.0, 1e-12);
+		// a line doesn't need a unit normal
+		assert_delta!(verify(&Axiom::Axiom1(a, b), Line { u: v(2.0, -2.0), d: 0.0 }), 0.0, 1e-12);
+		// every solution for many inputs
+		let points: Vec<Vector> = (0..4).flat_map(|i| (0..4).map(move |j| (i, j)))
+			.map(|(i, j)| v(0.1 + 0.27 * i as f64, 0.05 + 0.31 * j as f64))
+			.collect();
+		let lines: Vec<Line> = (0..6)
+			.map(|i| 0.3 + 0.5 * i as f64)
+			.map(|a: f64| {
+				let u = v(a.cos(), a.sin());
+				Line { u, d: v(0.5, 0.5).dot(u) - 0.1 }
+			})
+			.collect();
+		let mut axioms = vec![];
+		for p in points.iter() {
+			// the same point twice doesn't make a line
+			for q in points.iter().filter(|q| !q.equivalent(*p)) {
+				axioms.push(Axiom::Axiom1(*p, *q));
+				axioms.push(Axiom::Axiom2(*p, *q));
+				for l in lines.iter() {
+					axioms.push(Axiom::Axiom5(*p, *q, *l));
+					for m in lines.iter() { axioms.push(Axiom::Axiom6(*p, *q, *l, *m)); }
+				}
+			}
+			for l in lines.iter() {
+				axioms.push(Axiom::Axiom4(*p, *l));
+				for m in lines.iter() { axioms.push(Axiom::Axiom7(*p, *l, *m)); }
+			}
+		}
+		for l in lines.iter() {
+			for m in lines.iter() { axioms.push(Axiom::Axiom3(*l, *m)); }
+		}
+		let mut count = 0;
+		for axiom in axioms.iter() {
+			for solution in solve(axiom, square).unwrap() {
+				count += 1;
+				assert_eq!(solution.residual() < 1e-8, true);
+			}
+		}
+		assert_eq!(count > 1000, true);
+	}
+
+	#[test]
+	fn polynomial_tests () {
+		use ear::polynomial::{self, Root, Settings};
+		let values = |roots: Vec<Root>| roots.iter().map(|r| r.value).collect::<Vec<f64>>();
+		let multiplicities = |roots: Vec<Root>| roots.iter().map(|r| r.multiplicity).collect::<Vec<usize>>();
+		let near = |a: Vec<f64>, b: Vec<f64>| a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-9);
+		assert_eq!(near(values(polynomial::linear(2.0, -4.0)), vec![2.0]), true);
+		assert_eq!(near(values(polynomial::quadratic(1.0, -3.0, 2.0)), vec![1.0, 2.0]), true);
+		assert_eq!(polynomial::quadratic(1.0, 0.0, 1.0).is_empty(), true);
+		assert_eq!(polynomial::quadratic(1.0, -2.0, 1.0), vec![Root { value: 1.0, multiplicity: 2 }]);
+		// a leading coefficient that is almost zero is a lower degree
+		assert_eq!(near(values(polynomial::quadratic(1e-20, 2.0, -4.0)), vec![2.0]), true);
+		// the small root isn't lost to cancellation
+		let small = polynomial::quadratic(1.0, -1e8, 1.0);
+		assert_delta!(small[0].value, 1e-8, 1e-20);
+		// (x - 1)(x - 2)(x - 3)
+		assert_eq!(near(values(polynomial::cubic(1.0, -6.0, 11.0, -6.0)), vec![1.0, 2.0, 3.0]), true);
+		// double roots, on either side of the single root
+		let roots = polynomial::cubic(1.0, 0.0, -3.0, 2.0);
+		assert_eq!(near(values(roots.clone()), vec![-2.0, 1.0]), true);
+		assert_eq!(multiplicities(roots), vec![1, 2]);
+		let roots = polynomial::cubic(1.0, 0.0, -3.0, -2.0);
+		assert_eq!(near(values(roots.clone()), vec![-1.0, 2.0]), true);
+		assert_eq!(multiplicities(roots), vec![2, 1]);
+		assert_eq!(polynomial::cubic(1.0, 0.0, 0.0, 0.0), vec![Root { value: 0.0, multiplicity: 3 }]);
+		assert_eq!(near(values(polynomial::cubic(1.0, 0.0, 0.0, -8.0)), vec![2.0]), true);
+		// (x^2 - 1)(x^2 - 4), (x^2 - 1)^2, and x^4 + 1
+		assert_eq!(near(values(polynomial::quartic(1.0, 0.0, -5.0, 0.0, 4.0)), vec![-2.0, -1.0, 1.0, 2.0]), true);
+		assert_eq!(multiplicities(polynomial::quartic(1.0, 0.0, -2.0, 0.0, 1.0)), vec![2, 2]);
+		assert_eq!(polynomial::quartic(1.0, 0.0, 0.0, 0.0, 1.0).is_empty(), true);
+		// every root is a root
+		let p = [3.0, -2.5, -7.0, 1.0, 0.5];
+		for root in polynomial::solve(&p) { assert_delta!(polynomial::evaluate(&p, root.value), 0.0, 1e-12); }
+		assert_delta!(polynomial::polish(&[1.0, 0.0, -2.0], 1.4, 10), 2.0_f64.sqrt(), 1e-15);
+		// two roots very close together are one root, unless asked to look closer
+		let close = [1.0, -2.0, 1.0 - 1e-12];
+		assert_eq!(polynomial::solve(&close).len(), 1);
+		let fine = Settings { zero: 1e-15, merge: 1e-12, ..Settings::default() };
+		assert_eq!(polynomial::solve_with(&close, &fine).len(), 2);
+	}
+
+	#[test]
+	fn exact_tests () {
+		use ear::exact::{Number, Rational};
+		let n = |i: i64| Number::integer(i);
+		let sqrt = |x: &Number| x.sqrt().unwrap();
+		// fractions
+		let third = Rational::new(1, 3);
+		assert_eq!(&third + &Rational::new(1, 6), Rational::new(1, 2));
+		assert_eq!(Rational::new(2, -4), Rational::new(-1, 2));
+		assert_eq!(Rational::from_f64(0.1).unwrap() == Rational::new(1, 10), false);
+		assert_eq!(Rational::from_f64(0.375).unwrap(), Rational::new(3, 8));
+		assert_delta!(third.to_f64(), 1.0 / 3.0, EPSILON);
+		let big = (0..10).fold(Rational::one(), |p, _| &p * &Rational::integer(1 << 10));
+		assert_eq!(format!("{}", big), "1267650600228229401496703205376");
+		assert_eq!(format!("{}", &Rational::one() / &big), "1/1267650600228229401496703205376");
+		// the crease from the top left corner to the middle of the bottom
+		// crosses the diagonal a third of the way along (Haruki's theorem)
+		let intersect = |a: (Number, Number), b: (Number, Number), c: (Number, Number), d: (Number, Number)| {
+			let r = (&b.0 - &a.0, &b.1 - &a.1);
+			let s = (&d.0 - &c.0, &d.1 - &c.1);
+			let denominator = &(&r.0 * &s.1) - &(&r.1 * &s.0);
+			let t = &(&(&(&c.0 - &a.0) * &s.1) - &(&(&c.1 - &a.1) * &s.0)) / &denominator;
+			(&a.0 + &(&t * &r.0), &a.1 + &(&t * &r.1))
+		};
+		let p = intersect((n(0), n(0)), (n(1), n(1)), (n(0), n(1)), (Number::fraction(1, 2), n(0)));
+		assert_eq!(p.0, Number::fraction(1, 3));
+		assert_eq!(p.1, Number::fraction(1, 3));
+		// the left edge folded onto the diagonal crosses the top at sqrt(2) - 1
+		let root2 = sqrt(&n(2));
+		let direction = (&n(1) / &root2, &n(1) + &(&n(1) / &root2));
+		let p = intersect((n(0), n(0)), direction, (n(0), n(1)), (n(1), n(1)));
+		assert_eq!(p.0, &root2 - &n(1));
+		assert_eq!(&(&p.0 * &p.0) + &(&n(2) * &p.0), n(1));
+		assert_delta!(p.0.to_f64(), 2.0_f64.sqrt() - 1.0, 1e-15);
+		// square roots which are the same number, written differently
+		assert_eq!(&root2 * &root2, n(2));
+		assert_eq!(sqrt(&n(8)), &n(2) * &root2);
+		assert_eq!(sqrt(&(&n(3) + &(&n(2) * &root2))), &n(1) + &root2);
+		// the root is always the positive one
+		let root = sqrt(&(&n(3) - &(&n(2) * &root2)));
+		assert_eq!(root, &root2 - &n(1));
+		assert_eq!(root.signum(), 1);
+		let root3 = sqrt(&n(3));
+		let sum = &root2 + &root3;
+		assert_eq!(&sum * &sum, &n(5) + &(&n(2) * &sqrt(&n(6))));
+		assert_eq!(&n(1) / &(&root2 - &n(1)), &root2 + &n(1));
+		// and which are not
+		assert_eq!(root2 == root3, false);
+		assert_eq!(root2 < Number::fraction(3, 2) && Number::fraction(3, 2) < root3, true);
+		assert_eq!(&root2 + &root3 < sqrt(&n(10)), true);
+		assert_eq!(&(&root2 + &root3) - &sqrt(&(&n(5) + &(&n(2) * &sqrt(&n(6))))), n(0));
+		assert_eq!(n(-1).sqrt().is_none(), true);
+		// back to floats: every float survives the round trip,
+		// and fractions round to the nearest float
+		let floats = [
+			f64::MAX, -f64::MAX, f64::MIN_POSITIVE, 5e-324, -5e-324, 1e-310, 1e-300,
+			1e300, 0.1, -0.3, 1.0, 3.0, 1.0 / 3.0, 2.0_f64.sqrt(), 0.0,
+			f64::MIN_POSITIVE - 5e-324, 123456789.0e-200];
+		for x in floats.iter() {
+			assert_eq!(Rational::from_f64(*x).unwrap().to_f64(), *x);
+		}
+		assert_eq!(Rational::new(1, 3).to_f64(), 1.0 / 3.0);
+		assert_eq!(Rational::new(-2, 3).to_f64(), -2.0 / 3.0);
+		assert_eq!(Rational::new(1, 10).to_f64(), 0.1);
+		assert_eq!((&Rational::from_f64(f64::MAX).unwrap() * &Rational::integer(2)).to_f64(), f64::INFINITY);
+		assert_eq!((&Rational::from_f64(5e-324).unwrap() / &Rational::integer(3)).to_f64(), 0.0);
+		assert_eq!((&Rational::from_f64(5e-324).unwrap() * &Rational::new(2, 3)).to_f64(), 5e-324);
+		// halfway between two floats goes to the even one
+		let ulp = Rational::from_f64(f64::EPSILON).unwrap();
+		let half = &ulp * &Rational::new(1, 2);
+		assert_eq!((&Rational::one() + &half).to_f64(), 1.0);
+		assert_eq!((&Rational::one() + &(&half * &Rational::integer(3))).to_f64(), 1.0 + 2.0 * f64::EPSILON);
+	}
+
+	#[test]
+	fn scalar_tests () {
+		use ear::Scalar;
+		use ear::exact::Number;
+		fn square<T: Scalar> () -> Rect<T> {
+			let n = |x: f64| T::from_f64(x);
+			let line = |x: f64, y: f64, d: f64| Line { u: Vector { x: n(x), y: n(y) }, d: n(d) };
+			Rect { sides: [
+				line(0.0, -1.0, 0.0),
+				line(1.0, 0.0, 1.0),
+				line(0.0, 1.0, 1.0),
+				line(-1.0, 0.0, 0.0)
+			]}
+		}
+		// the same folds with f32
+		let v = |x: f32, y: f32| Vector { x, y };
+		let l = axioms::axiom2(v(0.0, 0.0), v(1.0, 1.0), square::<f32>());
+		assert_eq!(l.len(), 1);
+		assert_eq!((l[0].d - 0.5_f32.sqrt()).abs() < 1e-6, true);
+		let l = axioms::axiom3(square::<f32>().sides[0], square::<f32>().sides[3], square::<f32>());
+		assert_eq!(l.len(), 1);
+		let l = axioms::axiom5(v(0.0, 0.0), v(1.0, 0.0), square::<f32>().sides[2], square::<f32>());
+		assert_eq!(l.len(), 1);
+		assert_eq!(axioms::verify(&axioms::Axiom::Axiom5(v(0.0, 0.0), v(1.0, 0.0), square::<f32>().sides[2]), l[0]) < 1e-6, true);
+		let l = axioms::axiom6(v(0.2, 0.7), v(0.6, 0.2), square::<f32>().sides[2], square::<f32>().sides[1], square::<f32>()).unwrap();
+		assert_eq!(l.is_empty(), false);
+		// the same folds with exact numbers
+		let n = |i: i64| Number::integer(i);
+		let v = |x: i64, y: i64| Vector { x: n(x), y: n(y) };
+		let paper = square::<Number>();
+		// the diagonal, exactly 1 / sqrt(2) from the corner
+		let l = axioms::axiom2(v(0, 0), v(1, 1), &paper);
+		assert_eq!(l.len(), 1);
+		assert_eq!(&l[0].d * &l[0].d, Number::fraction(1, 2));
+		assert_eq!(l[0].u.x, l[0].u.y);
+		// the bottom edge onto the left, the other bisector is off the paper
+		let l = axioms::axiom3(paper.sides[0].clone(), paper.sides[3].clone(), &paper);
+		assert_eq!(l.len(), 1);
+		assert_eq!(l[0].d, n(0));
+		// the bottom edge is out of reach of the point, behind its normal
+		let f = |x: i64, y: i64| Vector { x: Number::fraction(x, 10), y: Number::fraction(y, 10) };
+		let bottom = Line { u: v(0, 1), d: n(0) };
+		assert_eq!(axioms::axiom5(f(5, 9), f(5, 8), bottom, &paper).len(), 0);
+		// through the bottom left corner, the bottom right corner onto the top
+		let axiom = axioms::Axiom::Axiom5(v(0, 0), v(1, 0), paper.sides[2].clone());
+		let solutions = axioms::solve(&axiom, &paper).unwrap();
+		assert_eq!(solutions.len(), 1);
+		assert_eq!(solutions[0].residual(), 0.0);
+		let mirror = solutions[0].mirrors[0].1.clone();
+		assert_eq!(mirror.x, n(0));
+		assert_eq!(mirror.y, n(1));
+		// a point fold: no tolerance, the point is exactly on the line
+		let l = axioms::axiom7(
+			Vector { x: Number::fraction(1, 4), y: Number::fraction(1, 2) },
+			paper.sides[0].clone(),
+			paper.sides[3].clone(),
+			&paper);
+		assert_eq!(l.len(), 1);
+		assert_eq!(l[0].d, Number::fraction(1, 8));
+		// axiom 6: this cubic has the roots 1/2 and (3 ± sqrt(3)) / 4
+		let f = |x: f64, y: f64| Vector { x: Number::from_f64(x).unwrap(), y: Number::from_f64(y).unwrap() };
+		let axiom = axioms::Axiom::Axiom6(f(0.25, 0.75), f(0.75, 0.25), paper.sides[2].clone(), paper.sides[1].clone());
+		let solutions = axioms::solve(&axiom, &paper).unwrap();
+		assert_eq!(solutions.is_empty(), false);
+		assert_eq!(solutions.iter().all(|s| s.residual() == 0.0), true);
+		// this one has no root that is a fraction, it can't be solved exactly. floats find one
+		let axiom = axioms::Axiom::Axiom6(f(0.25, 0.75), f(0.25, 0.5), paper.sides[2].clone(), paper.sides[1].clone());
+		assert_eq!(axioms::solve(&axiom, &paper).err(), Some(axioms::AxiomError::NotRepresentable));
+		assert_eq!(axioms::axiom6(f(0.25, 0.75), f(0.25, 0.5), paper.sides[2].clone(), paper.sides[1].clone(), &paper).is_err(), true);
+		let floats = square::<f64>();
+		let axiom = axioms::Axiom::Axiom6(Vector { x: 0.25, y: 0.75 }, Vector { x: 0.25, y: 0.5 }, floats.sides[2], floats.sides[1]);
+		assert_eq!(axioms::solve(&axiom, floats).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn tolerance_tests () {
+		use ear::Tolerance;
+		let tolerance: Tolerance = Tolerance::default();
+		assert_eq!(tolerance.distance, ear::EPSILON);
+		assert_delta!(Tolerance::for_size(1000.0).distance, 1000.0 * ear::EPSILON, EPSILON);
+		assert_eq!(Tolerance::for_size(1000.0).angle, tolerance.angle);
+		// a point just off the paper is on it, with a looser tolerance
+		let square = make_square();
+		let loose = Tolerance { distance: 1.0e-5, ..Tolerance::default() };
+		let p = Vector { x: 1.0 + 1.0e-6, y: 0.5 };
+		assert_eq!(square.contains(p), false);
+		assert_eq!(square.contains_with(p, &loose), true);
+		let polygon = Polygon::from(ConvexPolygon::from(square));
+		assert_eq!(polygon.contains(p), false);
+		assert_eq!(polygon.contains_with(p, &loose), true);
+		// past the corner of a long edge by more than the tolerance is off
+		let wide = Polygon::from(ConvexPolygon::from(make_rect(1000.0, 1000.0)));
+		assert_eq!(wide.contains_with(Vector { x: 1000.0 + 1.0e-6, y: 0.0 }, &loose), true);
+		assert_eq!(wide.contains_with(Vector { x: 1000.005, y: 0.0 }, &loose), false);
+		// a slot narrower than the tolerance doesn't cut a line in two
+		let slotted = Polygon::new(
+			&[Vector { x: 0.0, y: 0.0 }, Vector { x: 1.0, y: 0.0 }, Vector { x: 1.0, y: 1.0 }, Vector { x: 0.0, y: 1.0 }],
+			&[vec![Vector { x: 0.5, y: 0.25 }, Vector { x: 0.5 + 1.0e-6, y: 0.25 }, Vector { x: 0.5 + 1.0e-6, y: 0.75 }, Vector { x: 0.5, y: 0.75 }]]
+		).unwrap();
+		let across = Line { u: Vector { x: 0.0, y: 1.0 }, d: 0.5 };
+		assert_eq!(slotted.clip(across).len(), 2);
+		let pieces = slotted.clip_with(across, &loose);
+		assert_eq!(pieces.len(), 1);
+		assert_delta!(pieces[0].a.distance_to(pieces[0].b), 1.0, EPSILON);
+		// on paper a kilometre wide, a point 1e-7 off the edge is on it
+		let paper = make_rect(1000.0, 1000.0);
+		let big = Tolerance::for_size(1000.0);
+		let a = Vector { x: 500.0, y: 500.0 };
+		let b = Vector { x: 1000.0 + 1.0e-7, y: 500.0 };
+		assert_eq!(axioms::axiom1(a, b, paper).len(), 0);
+		assert_eq!(axioms::solve_with(&axioms::Axiom::Axiom1(a, b), paper, &big).unwrap().len(), 1);
+		// angles are the sine of the angle between the directions
+		let u = Vector { x: 1.0, y: 0.0 };
+		let v = Vector { x: 1.0, y: 1.0e-6 };
+		assert_eq!(u.parallel(v), false);
+		assert_eq!(u.parallel_with(v, &Tolerance { angle: 1.0e-5, ..Tolerance::default() }), true);
+		let l = Line { u: Vector { x: 0.0, y: 1.0 }, d: 0.5 };
+		let m = Line { u: v.rotate90().normalize(), d: 0.5 };
+		assert_eq!(l.intersect(m).0, true);
+		assert_eq!(l.intersect_with(m, &Tolerance { angle: 1.0e-5, ..Tolerance::default() }).0, false);
+		// axiom 6 with the first point close to (but not on) its line
+		let axiom = axioms::Axiom::Axiom6(
+			Vector { x: 0.3, y: 0.99 },
+			Vector { x: 0.7, y: 0.3 },
+			square.sides[2],
+			square.sides[1]);
+		let solutions = axioms::solve(&axiom, square).unwrap();
+		assert_eq!(solutions.is_empty(), false);
+		assert_eq!(solutions.iter().all(|s| s.residual() < 1e-9), true);
+		let on_line = axioms::Axiom::Axiom6(
+			Vector { x: 0.3, y: 1.0 },
+			Vector { x: 0.7, y: 0.3 },
+			square.sides[2],
+			square.sides[1]);
+		assert_eq!(axioms::solve(&on_line, square).unwrap().len(), 0);
+	}
+
+	#[test]
+	fn predicate_tests () {
+		use ear::predicates;
+		use ear::exact::Rational;
+		// the signs, checked with exact fractions, on points a few floats
+		// away from the line y = x, where the float determinant can be wrong
+		let r = |x: f64| Rational::from_f64(x).unwrap();
+		let exact_orient = |a: Vector, b: Vector, c: Vector| {
+			let abx = &r(b.x) - &r(a.x);
+			let aby = &r(b.y) - &r(a.y);
+			let acx = &r(c.x) - &r(a.x);
+			let acy = &r(c.y) - &r(a.y);
+			(&(&abx * &acy) - &(&aby * &acx)).signum()
+		};
+		let sign = |x: f64| if x > 0.0 { 1 } else if x < 0.0 { -1 } else { 0 };
+		let step = |x: f64, n: i64| f64::from_bits((x.to_bits() as i64 + n) as u64);
+		let b = Vector { x: 12.0, y: 12.0 };
+		let c = Vector { x: 24.0, y: 24.0 };
+		for i in -4..5 {
+			for j in -4..5 {
+				let a = Vector { x: step(0.5, i), y: step(0.5, j) };
+				assert_eq!(sign(predicates::orient2d(a, b, c)), exact_orient(a, b, c));
+				assert_eq!(predicates::collinear(a, b, c), i == j);
+			}
+		}
+		// which side of a line, for a line whose distance has been rounded
+		let u = Vector { x: 0.6, y: 0.8 };
+		for i in -4..5 {
+			let p = Vector { x: step(0.1, i), y: 0.2 };
+			let l = Line { u, d: 0.1 * 0.6 + 0.2 * 0.8 };
+			let exact = &(&(&r(p.x) * &r(u.x)) + &(&r(p.y) * &r(u.y))) - &r(l.d);
+			assert_eq!(sign(predicates::side(l, p)), exact.signum());
+		}
+		// a line through a corner only touches the paper,
+		// a line along an edge is the edge
+		let square = make_square();
+		let corner = Line { u: Vector { x: 1.0, y: 1.0 }, d: 2.0 };
+		assert_eq!(square.clip(corner).0, false);
+		let (success, edge) = square.clip(Line { u: Vector { x: 0.0, y: 1.0 }, d: 1.0 });
+		assert_eq!(success, true);
+		assert_delta!(edge.a.distance_to(edge.b), 1.0, EPSILON);
+		// collinear segments which only share an end still overlap
+		let s = Segment { a: Vector { x: 0.0, y: 0.0 }, b: Vector { x: 0.1, y: 0.3 } };
+		let t = Segment { a: Vector { x: 0.1, y: 0.3 }, b: Vector { x: 0.2, y: 0.6 } };
+		let v = Segment { a: Vector { x: 0.2, y: 0.6 }, b: Vector { x: 0.3, y: 0.9 } };
+		assert_eq!(s.quick_overlap(t), true);
+		assert_eq!(s.quick_overlap(v), false);
+	}
+
+	#[test]
+	fn segment_tests () {
+		use ear::Ray;
+		let v = |x: f64, y: f64| Vector { x, y };
+		let s = Segment { a: v(0.0, 0.0), b: v(1.0, 1.0) };
+		let t = Segment { a: v(0.0, 1.0), b: v(1.0, 0.0) };
+		// crossing, with the t along each
+		let (p, ts, tt) = s.intersect_segment(t).unwrap();
+		assert_delta!(p.x, 0.5, EPSILON);
+		assert_delta!(p.y, 0.5, EPSILON);
+		assert_delta!(ts, 0.5, EPSILON);
+		assert_delta!(tt, 0.5, EPSILON);
+		// touching at the end, short of each other, and parallel
+		assert_eq!(s.intersect_segment(Segment { a: v(1.0, 1.0), b: v(2.0, 0.0) }).is_some(), true);
+		assert_eq!(s.intersect_segment(Segment { a: v(2.0, 0.0), b: v(1.5, 0.5) }).is_none(), true);
+		assert_eq!(s.intersect_segment(Segment { a: v(0.0, 1.0), b: v(1.0, 2.0) }).is_none(), true);
+		// a line
+		let (p, along) = s.intersect_line(Line { u: v(1.0, 0.0), d: 0.25 }).unwrap();
+		assert_delta!(p.y, 0.25, EPSILON);
+		assert_delta!(along, 0.25, EPSILON);
+		assert_eq!(s.intersect_line(Line { u: v(1.0, 0.0), d: 2.0 }).is_none(), true);
+		// points on and near the segment
+		assert_eq!(s.contains(v(0.3, 0.3)), true);
+		assert_eq!(s.contains(v(1.0, 1.0)), true);
+		assert_eq!(s.contains(v(1.1, 1.1)), false);
+		assert_eq!(s.contains(v(0.3, 0.31)), false);
+		assert_eq!(s.closest_point(v(1.0, 0.0)).x, 0.5);
+		assert_delta!(s.distance_to(v(2.0, 1.0)), 1.0, EPSILON);
+		assert_delta!(s.distance_to(v(-3.0, -4.0)), 5.0, EPSILON);
+		// splitting, out of order, repeated, and at the ends
+		let pieces = s.split(&[0.75, 0.25, 0.0, 0.25, 1.0]);
+		assert_eq!(pieces.len(), 3);
+		assert_delta!(pieces[0].b.x, 0.25, EPSILON);
+		assert_delta!(pieces[1].b.x, 0.75, EPSILON);
+		assert_delta!(pieces[2].b.x, 1.0, EPSILON);
+		assert_eq!(s.split(&[]).len(), 1);
+		// rays only go forwards
+		let ray = Ray { origin: v(0.5, 0.0), vector: v(0.0, 1.0) };
+		let (p, along) = ray.intersect_line(Line { u: v(0.0, 1.0), d: 3.0 }).unwrap();
+		assert_delta!(p.y, 3.0, EPSILON);
+		assert_delta!(along, 3.0, EPSILON);
+		assert_eq!(ray.intersect_line(Line { u: v(0.0, 1.0), d: -1.0 }).is_none(), true);
+		let (p, _, u) = ray.intersect_segment(t).unwrap();
+		assert_delta!(p.y, 0.5, EPSILON);
+		assert_delta!(u, 0.5, EPSILON);
+		assert_eq!(ray.contains(v(0.5, 10.0)), true);
+		assert_eq!(ray.contains(v(0.5, -0.1)), false);
+		assert_delta!(ray.distance_to(v(0.5, -2.0)), 2.0, EPSILON);
+		// with no length, a segment or a ray is only its one point
+		let point = Segment { a: v(0.5, 0.5), b: v(0.5, 0.5) };
+		assert_eq!(point.contains(v(0.5, 0.5)), true);
+		assert_eq!(point.contains(v(0.5, 3.0)), false);
+		assert_eq!(point.contains(v(-2.0, 0.5)), false);
+		let still = Ray { origin: v(0.5, 0.5), vector: v(0.0, 0.0) };
+		assert_eq!(still.contains(v(0.5, 0.5)), true);
+		assert_eq!(still.contains(v(0.5, 3.0)), false);
+		assert_eq!(still.contains(v(-2.0, 7.0)), false);
+		// a new fold line, clipped to the paper, cuts the existing creases
+		let square = make_square();
+		let creases = [s, t];
+		let (success, fold) = square.clip(Line { u: v(0.0, 1.0), d: 0.25 });
+		assert_eq!(success, true);
+		let cuts: Vec<(f64, f64)> = creases.iter()
+			.filter_map(|crease| fold.intersect_segment(*crease).map(|(_, a, b)| (a, b)))
+			.collect();
+		assert_eq!(cuts.len(), 2);
+		let fold_pieces = fold.split(&cuts.iter().map(|c| c.0).collect::<Vec<f64>>());
+		assert_eq!(fold_pieces.len(), 3);
+		let crease_pieces: Vec<Segment> = creases.iter().zip(cuts.iter())
+			.flat_map(|(crease, cut)| crease.split(&[cut.1]))
+			.collect();
+		assert_eq!(crease_pieces.len(), 4);
+		assert_eq!(crease_pieces.iter().all(|piece| fold_pieces.iter()
+			.any(|f| f.contains(piece.a) || f.contains(piece.b))), true);
 	}
 
 	#[test]
@@ -168,6 +1395,10 @@ mod tests {
 		let point1 = Vector { x: 0.1, y: 0.0 };
 		let point2 = Vector { x: 0.9, y: 0.1 };
 		let _res = axioms::axiom5(point1, point2, line1, unit_square);
+		// the line is out of reach of the point, NaN lines are not on the paper
+		let far = Line { u: Vector { x: 0.0, y: 1.0 }, d: 0.0 };
+		assert_eq!(axioms::axiom5(Vector { x: 0.5, y: 0.9 }, Vector { x: 0.5, y: 0.8 }, far, unit_square).len(), 0);
+		assert_eq!(unit_square.contains(Vector { x: f64::NAN, y: 0.5 }), false);
 
 		// println!("axiom 5 #line input {:?}", line1);
 		// println!("axiom 5 #res({}): {:?}", res.len(), res);
@@ -200,7 +1431,7 @@ mod tests {
 		let line2 = make_line(&Vector { x: 1.0, y: 0.0 }, &Vector { x: 0.0, y: 1.0 });
 		let point1 = Vector { x: 0.75, y: 0.0 };
 		let point2 = Vector { x: 0.0, y: 0.75 };
-		let _res = axioms::axiom6(point1, point2, line1, line2, unit_square);
+		let _res = axioms::axiom6(point1, point2, line1, line2, unit_square).unwrap();
 
 		// println!("axiom 6 #res({}): {:?}", res.len(), res);
 